@@ -0,0 +1,150 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+//
+// Copyright (c) DUSK NETWORK. All rights reserved.
+
+use proc_macro2::{Literal, TokenStream};
+use quote::{quote, quote_spanned};
+use syn::spanned::Spanned;
+use syn::{parse_quote, Data, DeriveInput, Error, Fields, Result, Type};
+
+pub fn derive(input: DeriveInput) -> Result<TokenStream> {
+    let name = &input.ident;
+
+    let fields = match input.data {
+        Data::Struct(ref data) => &data.fields,
+        _ => {
+            return Err(Error::new(
+                input.span(),
+                "Annotation can only be derived for structs",
+            ))
+        }
+    };
+
+    // the accessor and the type of every field
+    let members: Vec<(TokenStream, &Type)> = match fields {
+        Fields::Named(fields) => fields
+            .named
+            .iter()
+            .map(|f| {
+                let ident = &f.ident;
+                (quote! { #ident }, &f.ty)
+            })
+            .collect(),
+        Fields::Unnamed(fields) => fields
+            .unnamed
+            .iter()
+            .enumerate()
+            .map(|(i, f)| {
+                let i = Literal::usize_unsuffixed(i);
+                (quote! { #i }, &f.ty)
+            })
+            .collect(),
+        Fields::Unit => {
+            return Err(Error::new(
+                input.span(),
+                "Annotation cannot be derived for unit structs",
+            ))
+        }
+    };
+
+    let (_, ty_generics, _) = input.generics.split_for_impl();
+
+    // `Annotation` requires `Default` and `Clone`, which might be derived
+    // with bounds on the type parameters
+    let self_ty = quote! { #name #ty_generics };
+    let supertraits = quote! { core::default::Default + core::clone::Clone };
+
+    // Annotation
+
+    let mut generics = input.generics.clone();
+    generics.params.push(parse_quote!(__L));
+    {
+        let where_clause = generics.make_where_clause();
+        where_clause
+            .predicates
+            .push(parse_quote!(#self_ty: #supertraits));
+        for (_, ty) in &members {
+            where_clause
+                .predicates
+                .push(parse_quote!(#ty: microkelvin::Annotation<__L>));
+        }
+    }
+    let (impl_generics, _, where_clause) = generics.split_for_impl();
+
+    let from_leaf = members.iter().map(|(member, ty)| {
+        quote_spanned! { ty.span() =>
+            #member: <#ty as microkelvin::Annotation<__L>>::from_leaf(leaf),
+        }
+    });
+
+    let annotation = quote! {
+        impl #impl_generics microkelvin::Annotation<__L>
+            for #name #ty_generics #where_clause
+        {
+            fn from_leaf(leaf: &__L) -> Self {
+                #name { #( #from_leaf )* }
+            }
+        }
+    };
+
+    // Combine
+
+    let mut generics = input.generics.clone();
+    generics.params.push(parse_quote!(__C));
+    generics.params.push(parse_quote!(__A));
+    {
+        let where_clause = generics.make_where_clause();
+        where_clause
+            .predicates
+            .push(parse_quote!(#self_ty: #supertraits));
+        where_clause
+            .predicates
+            .push(parse_quote!(__C: microkelvin::Compound<__A>));
+        for (_, ty) in &members {
+            where_clause
+                .predicates
+                .push(parse_quote!(#ty: microkelvin::Combine<__C, __A>));
+        }
+    }
+    let (impl_generics, _, where_clause) = generics.split_for_impl();
+
+    let combine = members.iter().map(|(member, ty)| {
+        quote_spanned! { ty.span() =>
+            #member: <#ty as microkelvin::Combine<__C, __A>>::combine(node),
+        }
+    });
+
+    let combine = quote! {
+        impl #impl_generics microkelvin::Combine<__C, __A>
+            for #name #ty_generics #where_clause
+        {
+            fn combine(node: &__C) -> Self {
+                #name { #( #combine )* }
+            }
+        }
+    };
+
+    // Borrow
+
+    let (impl_generics, _, where_clause) = input.generics.split_for_impl();
+
+    let borrows = members.iter().map(|(member, ty)| {
+        quote_spanned! { ty.span() =>
+            impl #impl_generics core::borrow::Borrow<#ty>
+                for #name #ty_generics #where_clause
+            {
+                fn borrow(&self) -> &#ty {
+                    &self.#member
+                }
+            }
+        }
+    });
+
+    Ok(quote! {
+        #annotation
+        #combine
+        #( #borrows )*
+    })
+}
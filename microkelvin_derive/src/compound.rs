@@ -0,0 +1,295 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+//
+// Copyright (c) DUSK NETWORK. All rights reserved.
+
+use proc_macro2::{Ident, TokenStream};
+use quote::{quote, quote_spanned};
+use syn::spanned::Spanned;
+use syn::{
+    parse_quote, Data, DeriveInput, Error, Field, Fields, GenericArgument,
+    Index, PathArguments, Result, Type,
+};
+
+/// Whether a field holds leaves or annotated subtrees
+#[derive(Clone, Copy, PartialEq)]
+enum Kind {
+    Leaf,
+    Node,
+}
+
+/// A field marked with `#[leaf]` or `#[node]`
+struct Slot {
+    kind: Kind,
+    binding: Ident,
+    /// The field is a `Vec` or an array of children
+    sequence: bool,
+    /// The children are wrapped in an `Option`, `None` being an empty slot
+    optional: bool,
+    /// The type of the leaf or the annotated node itself
+    inner: Type,
+}
+
+/// The fields of a struct, or of one variant of an enum
+struct Shape {
+    pattern: TokenStream,
+    slots: Vec<Slot>,
+}
+
+fn kind(field: &Field) -> Result<Option<Kind>> {
+    let mut kind = None;
+    for attr in &field.attrs {
+        let this = if attr.path.is_ident("leaf") {
+            Kind::Leaf
+        } else if attr.path.is_ident("node") {
+            Kind::Node
+        } else {
+            continue;
+        };
+        if kind.is_some() {
+            return Err(Error::new(
+                attr.span(),
+                "A field can only be either a leaf or a node",
+            ));
+        }
+        kind = Some(this);
+    }
+    Ok(kind)
+}
+
+/// Returns the single generic argument of a type named `wrapper`, if any
+fn unwrap<'a>(ty: &'a Type, wrapper: &str) -> Option<&'a Type> {
+    if let Type::Path(path) = ty {
+        let segment = path.path.segments.last()?;
+        if segment.ident != wrapper {
+            return None;
+        }
+        if let PathArguments::AngleBracketed(args) = &segment.arguments {
+            if let Some(GenericArgument::Type(inner)) = args.args.first() {
+                return Some(inner);
+            }
+        }
+    }
+    None
+}
+
+fn slot(field: &Field, kind: Kind, binding: Ident) -> Slot {
+    let (sequence, element) = match &field.ty {
+        Type::Array(array) => (true, &*array.elem),
+        ty => match unwrap(ty, "Vec") {
+            Some(element) => (true, element),
+            None => (false, ty),
+        },
+    };
+
+    let (optional, inner) = match unwrap(element, "Option") {
+        Some(inner) => (true, inner),
+        None => (false, element),
+    };
+
+    Slot {
+        kind,
+        binding,
+        sequence,
+        optional,
+        inner: inner.clone(),
+    }
+}
+
+fn shape(path: TokenStream, fields: &Fields) -> Result<Shape> {
+    let mut slots = vec![];
+    let mut bindings = vec![];
+
+    for (i, field) in fields.iter().enumerate() {
+        if let Some(kind) = kind(field)? {
+            let binding = Ident::new(&format!("__field_{}", i), field.span());
+            let member = match &field.ident {
+                Some(ident) => quote! { #ident },
+                None => {
+                    let i = Index::from(i);
+                    quote! { #i }
+                }
+            };
+            bindings.push(quote! { #member: #binding });
+            slots.push(slot(field, kind, binding));
+        }
+    }
+
+    Ok(Shape {
+        pattern: quote! { #path { #( #bindings, )* .. } },
+        slots,
+    })
+}
+
+/// The generated body of a match arm for either `child` or `child_mut`
+fn arm(shape: &Shape, mutable: bool) -> TokenStream {
+    let (child, as_ref, borrow) = if mutable {
+        (
+            quote! { microkelvin::ChildMut },
+            quote! { as_mut },
+            quote! { &mut },
+        )
+    } else {
+        (
+            quote! { microkelvin::Child },
+            quote! { as_ref },
+            quote! { & },
+        )
+    };
+
+    let mut base = quote! { 0usize };
+    let mut checks = vec![];
+
+    for slot in &shape.slots {
+        let binding = &slot.binding;
+        let variant = match slot.kind {
+            Kind::Leaf => quote! { Leaf },
+            Kind::Node => quote! { Node },
+        };
+
+        let (len, element) = if slot.sequence {
+            (
+                quote! { #binding.len() },
+                quote! { #binding[ofs - (#base)] },
+            )
+        } else {
+            (quote! { 1usize }, quote! { (*#binding) })
+        };
+
+        let found = if slot.optional {
+            quote! {
+                match #element.#as_ref() {
+                    Some(child) => #child::#variant(child),
+                    None => #child::Empty,
+                }
+            }
+        } else {
+            quote! { #child::#variant(#borrow #element) }
+        };
+
+        let end = quote! { #base + #len };
+        checks.push(quote_spanned! { binding.span() =>
+            if ofs < #end {
+                return #found;
+            }
+        });
+        base = end;
+    }
+
+    let pattern = &shape.pattern;
+    quote! {
+        #pattern => {
+            #( #checks )*
+            #child::EndOfNode
+        }
+    }
+}
+
+/// Finds the first type in a slot of `kind` across all shapes
+fn first_of(shapes: &[Shape], kind: Kind) -> Option<&Type> {
+    shapes
+        .iter()
+        .flat_map(|shape| shape.slots.iter())
+        .find(|slot| slot.kind == kind)
+        .map(|slot| &slot.inner)
+}
+
+/// Extracts `A` from `Annotated<C, A>`
+fn annotation_of(node: &Type) -> Result<Type> {
+    if let Type::Path(path) = node {
+        if let Some(segment) = path.path.segments.last() {
+            if let PathArguments::AngleBracketed(args) = &segment.arguments {
+                if let Some(GenericArgument::Type(ty)) = args.args.iter().nth(1)
+                {
+                    return Ok(ty.clone());
+                }
+            }
+        }
+    }
+    Err(Error::new(
+        node.span(),
+        "Node fields must be of type `Annotated<Self, A>`",
+    ))
+}
+
+pub fn derive(input: DeriveInput) -> Result<TokenStream> {
+    let name = &input.ident;
+
+    let shapes = match &input.data {
+        Data::Struct(data) => vec![shape(quote! { #name }, &data.fields)?],
+        Data::Enum(data) => data
+            .variants
+            .iter()
+            .map(|v| {
+                let ident = &v.ident;
+                shape(quote! { #name::#ident }, &v.fields)
+            })
+            .collect::<Result<_>>()?,
+        Data::Union(_) => {
+            return Err(Error::new(
+                input.span(),
+                "Compound cannot be derived for unions",
+            ))
+        }
+    };
+
+    let leaf = first_of(&shapes, Kind::Leaf).cloned().ok_or_else(|| {
+        Error::new(input.span(), "Compound requires at least one #[leaf] field")
+    })?;
+
+    let (_, ty_generics, _) = input.generics.split_for_impl();
+    let mut generics = input.generics.clone();
+
+    // The annotation is taken from the nodes, collections without them are
+    // compounds under any annotation.
+    let annotation = match first_of(&shapes, Kind::Node) {
+        Some(node) => annotation_of(node)?,
+        None => {
+            generics.params.push(parse_quote!(__A));
+            parse_quote!(__A)
+        }
+    };
+
+    generics
+        .make_where_clause()
+        .predicates
+        .push(parse_quote!(#name #ty_generics: canonical::Canon));
+
+    let (impl_generics, _, where_clause) = generics.split_for_impl();
+
+    let child = shapes.iter().map(|shape| arm(shape, false));
+    let child_mut = shapes.iter().map(|shape| arm(shape, true));
+
+    Ok(quote! {
+        impl #impl_generics microkelvin::Compound<#annotation>
+            for #name #ty_generics #where_clause
+        {
+            type Leaf = #leaf;
+
+            fn child(
+                &self,
+                ofs: usize,
+            ) -> microkelvin::Child<'_, Self, #annotation>
+            where
+                #annotation: microkelvin::Annotation<Self::Leaf>,
+            {
+                match self {
+                    #( #child )*
+                }
+            }
+
+            fn child_mut(
+                &mut self,
+                ofs: usize,
+            ) -> microkelvin::ChildMut<'_, Self, #annotation>
+            where
+                #annotation: microkelvin::Annotation<Self::Leaf>,
+            {
+                match self {
+                    #( #child_mut )*
+                }
+            }
+        }
+    })
+}
@@ -0,0 +1,54 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+//
+// Copyright (c) DUSK NETWORK. All rights reserved.
+
+//! Derives for the `microkelvin` traits
+
+#![deny(missing_docs)]
+
+use syn::{parse_macro_input, DeriveInput};
+
+mod annotation;
+mod compound;
+
+#[proc_macro_derive(Annotation)]
+/// Derive macro that implements `Annotation`, `Combine` and `Borrow` for a
+/// struct made up of annotations.
+///
+/// Every field is created from the leaf and combined independently, and the
+/// struct can be borrowed as the type of any of its fields, so that walkers
+/// such as `Nth` or `GetMaxKey` can be used on collections annotated with it.
+///
+/// Since a `Borrow` implementation is generated per field, no two fields may
+/// share the same type.
+pub fn annotation_derive(
+    input: proc_macro::TokenStream,
+) -> proc_macro::TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+
+    annotation::derive(input)
+        .unwrap_or_else(|e| e.to_compile_error())
+        .into()
+}
+
+#[proc_macro_derive(Compound, attributes(leaf, node))]
+/// Derive macro that implements `Compound` for a struct or an enum.
+///
+/// Fields marked with `#[leaf]` hold leaves and fields marked with `#[node]`
+/// hold `Annotated<Self, A>` subtrees. Both can also be a `Vec` or an array of
+/// children, and children wrapped in an `Option` are reported as empty slots
+/// when `None`.
+///
+/// Children are numbered in field order, and `child` and `child_mut` are
+/// generated from the same description so that they always agree.
+pub fn compound_derive(
+    input: proc_macro::TokenStream,
+) -> proc_macro::TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+
+    compound::derive(input)
+        .unwrap_or_else(|e| e.to_compile_error())
+        .into()
+}
@@ -0,0 +1,162 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+//
+// Copyright (c) DUSK NETWORK. All rights reserved.
+
+/// Annotation to keep track of the number of bytes in a collection of byte
+/// chunks
+use core::borrow::Borrow;
+
+use canonical::CanonError;
+use canonical_derive::Canon;
+
+use crate::annotations::{Annotation, Combine};
+use crate::branch::Branch;
+use crate::branch_mut::BranchMut;
+use crate::compound::{Child, Compound, MutableLeaves};
+use crate::walk::{Step, Walk, Walker};
+
+/// The number of bytes in a compound collection of byte chunks
+#[derive(Canon, PartialEq, Debug, Clone, Default, Copy)]
+pub struct ByteLen(pub(crate) u64);
+
+impl From<ByteLen> for u64 {
+    fn from(len: ByteLen) -> Self {
+        len.0
+    }
+}
+
+impl<'a> From<&'a ByteLen> for u64 {
+    fn from(len: &'a ByteLen) -> Self {
+        len.0
+    }
+}
+
+impl<L> Annotation<L> for ByteLen
+where
+    L: AsRef<[u8]>,
+{
+    fn from_leaf(leaf: &L) -> Self {
+        ByteLen(leaf.as_ref().len() as u64)
+    }
+}
+
+impl<C, A> Combine<C, A> for ByteLen
+where
+    C: Compound<A>,
+    C::Leaf: AsRef<[u8]>,
+    A: Annotation<C::Leaf> + Borrow<Self>,
+{
+    fn combine(node: &C) -> Self {
+        let mut sum = 0;
+        for child in node.children() {
+            let ann = &*child.annotation();
+            let len: &ByteLen = ann.borrow();
+            sum += len.0
+        }
+        ByteLen(sum)
+    }
+}
+
+/// Walker to find the chunk in which a byte offset falls
+///
+/// Once found, the walker holds the offset of the byte in its chunk.
+pub struct ByteOffset(u64);
+
+impl ByteOffset {
+    /// Creates a walker searching for the byte at offset `ofs`
+    pub fn new(ofs: u64) -> Self {
+        ByteOffset(ofs)
+    }
+
+    /// Returns the offset left to walk, which is the offset in the found
+    /// chunk after a successful walk
+    pub fn remaining(&self) -> u64 {
+        self.0
+    }
+}
+
+impl<C, A> Walker<C, A> for ByteOffset
+where
+    C: Compound<A>,
+    C::Leaf: AsRef<[u8]>,
+    A: Combine<C, A> + Borrow<ByteLen>,
+{
+    fn walk(&mut self, walk: Walk<C, A>) -> Step {
+        for i in 0.. {
+            match walk.child(i) {
+                Child::Leaf(l) => {
+                    let len = l.as_ref().len() as u64;
+
+                    if len <= self.0 {
+                        self.0 -= len;
+                    } else {
+                        return Step::Found(i);
+                    }
+                }
+                Child::Node(node) => {
+                    let len: u64 = node.annotation().borrow().into();
+
+                    if len <= self.0 {
+                        self.0 -= len;
+                    } else {
+                        return Step::Into(i);
+                    }
+                }
+                Child::Empty => (),
+                Child::EndOfNode => return Step::Abort,
+            }
+        }
+        unreachable!()
+    }
+}
+
+/// Trait that provides `nth_byte()` and `nth_byte_mut()` methods to any
+/// Compound of byte chunks with a ByteLen annotation
+pub trait NthByte<'a, A>
+where
+    Self: Compound<A>,
+    Self::Leaf: AsRef<[u8]>,
+    A: Combine<Self, A> + Borrow<ByteLen>,
+{
+    /// Construct a `Branch` pointing to the chunk holding the byte at offset
+    /// `ofs`, if any
+    fn nth_byte(
+        &'a self,
+        ofs: u64,
+    ) -> Result<Option<Branch<'a, Self, A>>, CanonError>;
+
+    /// Construct a `BranchMut` pointing to the chunk holding the byte at
+    /// offset `ofs`, if any
+    fn nth_byte_mut(
+        &'a mut self,
+        ofs: u64,
+    ) -> Result<Option<BranchMut<'a, Self, A>>, CanonError>
+    where
+        Self: MutableLeaves;
+}
+
+impl<'a, C, A> NthByte<'a, A> for C
+where
+    C: Compound<A> + Clone,
+    C::Leaf: AsRef<[u8]>,
+    A: Combine<C, A> + Borrow<ByteLen>,
+{
+    fn nth_byte(
+        &'a self,
+        ofs: u64,
+    ) -> Result<Option<Branch<'a, Self, A>>, CanonError> {
+        Branch::<_, A>::walk(self, ByteOffset(ofs))
+    }
+
+    fn nth_byte_mut(
+        &'a mut self,
+        ofs: u64,
+    ) -> Result<Option<BranchMut<'a, Self, A>>, CanonError>
+    where
+        C: MutableLeaves,
+    {
+        BranchMut::<_, A>::walk(self, ByteOffset(ofs))
+    }
+}
@@ -55,8 +55,59 @@ where
     }
 }
 
+// The number of leaves in the children of `node` before `ofs`
+pub(crate) fn leaves_before<C, A>(node: &C, ofs: usize) -> u64
+where
+    C: Compound<A>,
+    A: Combine<C, A> + Borrow<Cardinality>,
+{
+    let mut sum = 0;
+    for i in 0..ofs {
+        match node.child(i) {
+            Child::Leaf(_) => sum += 1,
+            Child::Node(n) => {
+                let card: u64 = n.annotation().borrow().into();
+                sum += card
+            }
+            Child::Empty => (),
+            Child::EndOfNode => break,
+        }
+    }
+    sum
+}
+
+impl<'a, C, A> Branch<'a, C, A>
+where
+    C: Compound<A>,
+    A: Combine<C, A> + Borrow<Cardinality>,
+{
+    /// Returns the index of the leaf pointed at by the branch, such that
+    /// `nth(branch.index())` points at the same leaf
+    pub fn index(&self) -> u64 {
+        self.levels()
+            .iter()
+            .map(|level| leaves_before::<C, A>(level, level.offset()))
+            .sum()
+    }
+}
+
+impl<'a, C, A> BranchMut<'a, C, A>
+where
+    C: Compound<A>,
+    A: Combine<C, A> + Borrow<Cardinality>,
+{
+    /// Returns the index of the leaf pointed at by the branch, such that
+    /// `nth_mut(branch.index())` points at the same leaf
+    pub fn index(&self) -> u64 {
+        self.levels()
+            .iter()
+            .map(|level| leaves_before::<C, A>(level, level.offset()))
+            .sum()
+    }
+}
+
 /// Walker method to find the nth element of a compound collection
-pub struct Offset(u64);
+pub struct Offset(pub(crate) u64);
 
 impl<C, A> Walker<C, A> for Offset
 where
@@ -108,6 +159,21 @@ where
     ) -> Result<Option<BranchMut<'a, Self, A>>, CanonError>
     where
         Self: MutableLeaves;
+
+    /// Construct a `Branch` pointing to the `nth` element from the end, if any
+    fn nth_back(
+        &'a self,
+        n: u64,
+    ) -> Result<Option<Branch<'a, Self, A>>, CanonError>;
+
+    /// Construct a `BranchMut` pointing to the `nth` element from the end, if
+    /// any
+    fn nth_back_mut(
+        &'a mut self,
+        n: u64,
+    ) -> Result<Option<BranchMut<'a, Self, A>>, CanonError>
+    where
+        Self: MutableLeaves;
 }
 
 impl<'a, C, A> Nth<'a, A> for C
@@ -134,4 +200,22 @@ where
         // Return the first mutable branch that satisfies the walk
         BranchMut::<_, A>::walk(self, Offset(ofs))
     }
+
+    fn nth_back(
+        &'a self,
+        ofs: u64,
+    ) -> Result<Option<Branch<'a, Self, A>>, CanonError> {
+        // Offsets count from the end when walking backward
+        Branch::<_, A>::walk_back(self, Offset(ofs))
+    }
+
+    fn nth_back_mut(
+        &'a mut self,
+        ofs: u64,
+    ) -> Result<Option<BranchMut<'a, Self, A>>, CanonError>
+    where
+        C: MutableLeaves,
+    {
+        BranchMut::<_, A>::walk_back(self, Offset(ofs))
+    }
 }
@@ -0,0 +1,89 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+//
+// Copyright (c) DUSK NETWORK. All rights reserved.
+
+use alloc::vec::Vec;
+
+use canonical::CanonError;
+
+use crate::annotations::{Annotated, Combine};
+use crate::compound::{Child, Compound};
+use crate::path::BranchPath;
+
+/// A node whose cached annotation differs from the one combined from its
+/// children
+#[derive(Clone, Debug, PartialEq)]
+pub struct AnnotationMismatch<A> {
+    /// The offsets of the node from the root
+    pub path: BranchPath,
+    /// The annotation cached in the parent of the node
+    pub cached: A,
+    /// The annotation combined from the children of the node
+    pub combined: A,
+}
+
+/// Checks the cached annotation of every node below `root`, returning the
+/// nodes where it differs from the annotation combined from their children
+///
+/// Nodes are compared against the cached annotations of their own children,
+/// so a stale annotation is reported at the node directly above the change,
+/// not at every node on the way to the root. The nodes are reported in the
+/// order of their paths.
+pub fn verify_annotations<C, A>(
+    root: &C,
+) -> Result<Vec<AnnotationMismatch<A>>, CanonError>
+where
+    C: Compound<A>,
+    A: Combine<C, A> + PartialEq,
+{
+    let mut mismatches = Vec::new();
+
+    // explicit stack instead of recursion, to handle deep collections. Each
+    // node is pushed with its depth and offset, and the path to the node
+    // popped last is kept in a single vector.
+    let mut stack: Vec<Pending<C, A>> = Vec::new();
+    let mut path = Vec::new();
+    push_children(root, 0, &mut stack);
+
+    while let Some((depth, ofs, annotated)) = stack.pop() {
+        path.truncate(depth);
+        path.push(ofs);
+
+        let node = annotated.val()?;
+
+        let combined = A::combine(&*node);
+        if combined != *annotated.annotation() {
+            mismatches.push(AnnotationMismatch {
+                path: BranchPath::from(&path[..]),
+                cached: annotated.annotation().clone(),
+                combined,
+            });
+        }
+
+        push_children(&*node, path.len(), &mut stack);
+    }
+
+    Ok(mismatches)
+}
+
+// A node left to check, with its depth and its offset in its parent
+type Pending<C, A> = (usize, usize, Annotated<C, A>);
+
+// Pushes the child nodes of `node` on the stack, last one first
+fn push_children<C, A>(node: &C, depth: usize, stack: &mut Vec<Pending<C, A>>)
+where
+    C: Compound<A>,
+    A: Combine<C, A>,
+{
+    let start = stack.len();
+    for i in 0.. {
+        match node.child(i) {
+            Child::Node(annotated) => stack.push((depth, i, annotated.clone())),
+            Child::EndOfNode => break,
+            _ => (),
+        }
+    }
+    stack[start..].reverse();
+}
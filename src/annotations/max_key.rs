@@ -15,13 +15,14 @@ use canonical_derive::Canon;
 use crate::annotations::{Annotation, Combine};
 use crate::branch::Branch;
 use crate::branch_mut::BranchMut;
-use crate::compound::{Child, Compound, MutableLeaves};
+use crate::compound::{Child, Compound, MutableLeaves, SortedLeaves};
 use crate::walk::{Step, Walk, Walker};
 
 /// The maximum value of a collection
-#[derive(Canon, PartialEq, Eq, Debug, Clone, Copy)]
+#[derive(Canon, PartialEq, Eq, Debug, Clone, Copy, Default)]
 pub enum MaxKey<K> {
     /// Identity of max, everything else is larger
+    #[default]
     NegativeInfinity,
     /// Actual max value
     Maximum(K),
@@ -40,12 +41,6 @@ impl<T> Keyed<T> for T {
     }
 }
 
-impl<K> Default for MaxKey<K> {
-    fn default() -> Self {
-        MaxKey::NegativeInfinity
-    }
-}
-
 impl<K> PartialOrd for MaxKey<K>
 where
     K: PartialOrd,
@@ -199,3 +194,95 @@ where
         BranchMut::<_, A>::walk(self, FindMaxKey::default())
     }
 }
+
+/// Walker to find the leaf with a specific key in a sorted collection
+pub struct FindKey<'k, K>(&'k K);
+
+impl<'k, K> FindKey<'k, K> {
+    /// Creates a walker searching for `key`
+    pub fn new(key: &'k K) -> Self {
+        FindKey(key)
+    }
+}
+
+impl<'k, C, A, K> Walker<C, A> for FindKey<'k, K>
+where
+    C: Compound<A> + SortedLeaves,
+    C::Leaf: Keyed<K>,
+    A: Combine<C, A> + Borrow<MaxKey<K>>,
+    K: Ord,
+{
+    fn walk(&mut self, walk: Walk<C, A>) -> Step {
+        for i in 0.. {
+            match walk.child(i) {
+                Child::Leaf(l) => match l.key().cmp(self.0) {
+                    Ordering::Less => (),
+                    Ordering::Equal => return Step::Found(i),
+                    // Since the leaves are sorted, the key is not present
+                    Ordering::Greater => return Step::Abort,
+                },
+                Child::Node(n) => {
+                    if let MaxKey::Maximum(max) = n.annotation().borrow() {
+                        if max >= self.0 {
+                            return Step::Into(i);
+                        }
+                    }
+                }
+                Child::Empty => (),
+                Child::EndOfNode => return Step::Abort,
+            }
+        }
+        unreachable!()
+    }
+}
+
+/// Trait that provides `get()` and `get_mut()` methods to any sorted Compound
+/// with a MaxKey annotation
+pub trait GetKey<'a, A, K>
+where
+    Self: Compound<A> + SortedLeaves,
+    Self::Leaf: Keyed<K>,
+    A: Combine<Self, A> + Borrow<MaxKey<K>>,
+    K: Ord,
+{
+    /// Construct a `Branch` pointing to the element with key `key`, if any
+    fn get(
+        &'a self,
+        key: &K,
+    ) -> Result<Option<Branch<'a, Self, A>>, CanonError>;
+
+    /// Construct a `BranchMut` pointing to the element with key `key`, if any
+    fn get_mut(
+        &'a mut self,
+        key: &K,
+    ) -> Result<Option<BranchMut<'a, Self, A>>, CanonError>
+    where
+        Self: MutableLeaves;
+}
+
+impl<'a, C, A, K> GetKey<'a, A, K> for C
+where
+    C: Compound<A> + SortedLeaves + Clone,
+    C::Leaf: Keyed<K>,
+    A: Combine<C, A> + Borrow<MaxKey<K>>,
+    K: Ord,
+{
+    fn get(
+        &'a self,
+        key: &K,
+    ) -> Result<Option<Branch<'a, Self, A>>, CanonError> {
+        // Return the first that satisfies the walk
+        Branch::<_, A>::walk(self, FindKey(key))
+    }
+
+    fn get_mut(
+        &'a mut self,
+        key: &K,
+    ) -> Result<Option<BranchMut<'a, Self, A>>, CanonError>
+    where
+        C: MutableLeaves,
+    {
+        // Return the first mutable branch that satisfies the walk
+        BranchMut::<_, A>::walk(self, FindKey(key))
+    }
+}
@@ -0,0 +1,259 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+//
+// Copyright (c) DUSK NETWORK. All rights reserved.
+
+/// Annotation to keep track of the merkle root of a collection
+use core::borrow::Borrow;
+use core::cell::OnceCell;
+use core::fmt;
+use core::iter;
+use core::marker::PhantomData;
+
+use alloc::vec::Vec;
+
+use canonical::{Canon, CanonError, EncodeToVec, Sink, Source};
+use canonical_derive::Canon;
+
+use crate::annotations::{Annotation, Combine};
+use crate::branch::Branch;
+use crate::compound::{Child, Compound};
+
+// Domain separation between the hashes of leaves, of nodes and of empty slots
+const LEAF_TAG: u8 = 0;
+const NODE_TAG: u8 = 1;
+const EMPTY_TAG: u8 = 2;
+
+/// Trait for the hash functions used to compute merkle roots
+pub trait MerkleHasher {
+    /// The output of the hash function
+    type Digest: Canon + AsRef<[u8]> + Default + PartialEq + fmt::Debug;
+
+    /// Hashes the concatenation of `parts`
+    fn hash<'a, I>(parts: I) -> Self::Digest
+    where
+        I: IntoIterator<Item = &'a [u8]>;
+}
+
+/// The default hasher, BLAKE2b with a 32 byte output
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Blake2b;
+
+impl MerkleHasher for Blake2b {
+    type Digest = [u8; 32];
+
+    fn hash<'a, I>(parts: I) -> Self::Digest
+    where
+        I: IntoIterator<Item = &'a [u8]>,
+    {
+        let mut state = blake2b_simd::Params::new().hash_length(32).to_state();
+        for part in parts {
+            state.update(part);
+        }
+        let mut digest = [0u8; 32];
+        digest.copy_from_slice(state.finalize().as_bytes());
+        digest
+    }
+}
+
+/// The merkle root of a compound collection
+pub struct MerkleRoot<H: MerkleHasher = Blake2b>(H::Digest, PhantomData<H>);
+
+impl<H: MerkleHasher> MerkleRoot<H> {
+    /// Returns the digest of the root
+    pub fn digest(&self) -> &H::Digest {
+        &self.0
+    }
+
+    fn of_leaf<L: Canon>(leaf: &L) -> Self {
+        let bytes = leaf.encode_to_vec();
+        let digest =
+            H::hash(iter::once(&[LEAF_TAG][..]).chain(iter::once(&bytes[..])));
+        MerkleRoot(digest, PhantomData)
+    }
+
+    // Empty slots are hashed as the tag on its own, which no leaf or node
+    // digest can be mistaken for. It is only computed for nodes that have
+    // empty slots.
+    fn of_node<'a, I>(children: I) -> Self
+    where
+        I: IntoIterator<Item = Option<&'a H::Digest>>,
+        H::Digest: 'a,
+    {
+        let empty = OnceCell::new();
+        let children = children.into_iter().map(|child| match child {
+            Some(digest) => digest.as_ref(),
+            None => empty
+                .get_or_init(|| H::hash(iter::once(&[EMPTY_TAG][..])))
+                .as_ref(),
+        });
+        let digest = H::hash(iter::once(&[NODE_TAG][..]).chain(children));
+        MerkleRoot(digest, PhantomData)
+    }
+}
+
+impl<H: MerkleHasher> Clone for MerkleRoot<H> {
+    fn clone(&self) -> Self {
+        MerkleRoot(self.0.clone(), PhantomData)
+    }
+}
+
+impl<H: MerkleHasher> Default for MerkleRoot<H> {
+    fn default() -> Self {
+        MerkleRoot(H::Digest::default(), PhantomData)
+    }
+}
+
+impl<H: MerkleHasher> PartialEq for MerkleRoot<H> {
+    fn eq(&self, other: &Self) -> bool {
+        self.0 == other.0
+    }
+}
+
+impl<H: MerkleHasher> fmt::Debug for MerkleRoot<H> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("MerkleRoot").field(&self.0).finish()
+    }
+}
+
+impl<H: MerkleHasher> Canon for MerkleRoot<H> {
+    fn encode(&self, sink: &mut Sink) {
+        self.0.encode(sink)
+    }
+
+    fn decode(source: &mut Source) -> Result<Self, CanonError> {
+        Ok(MerkleRoot(H::Digest::decode(source)?, PhantomData))
+    }
+
+    fn encoded_len(&self) -> usize {
+        self.0.encoded_len()
+    }
+}
+
+impl<L, H> Annotation<L> for MerkleRoot<H>
+where
+    L: Canon,
+    H: MerkleHasher,
+{
+    fn from_leaf(leaf: &L) -> Self {
+        MerkleRoot::of_leaf(leaf)
+    }
+}
+
+impl<C, A, H> Combine<C, A> for MerkleRoot<H>
+where
+    C: Compound<A>,
+    C::Leaf: Canon,
+    A: Annotation<C::Leaf> + Borrow<Self>,
+    H: MerkleHasher,
+{
+    fn combine(node: &C) -> Self {
+        let mut digests = vec![];
+        for i in 0.. {
+            match node.child(i) {
+                Child::Leaf(l) => digests.push(Some(Self::of_leaf(l).0)),
+                Child::Node(n) => {
+                    let root: &Self = n.annotation().borrow();
+                    digests.push(Some(root.0.clone()))
+                }
+                Child::Empty => digests.push(None),
+                Child::EndOfNode => break,
+            }
+        }
+        MerkleRoot::of_node(digests.iter().map(Option::as_ref))
+    }
+}
+
+/// The siblings of one node on the path from a leaf to the root
+///
+/// Empty slots are represented by `None`
+#[derive(Canon, Clone, Debug, PartialEq)]
+pub struct ProofLevel<D> {
+    /// The digests of the children before the one on the path
+    pub before: Vec<Option<D>>,
+    /// The digests of the children after the one on the path
+    pub after: Vec<Option<D>>,
+}
+
+/// A proof of inclusion of a leaf in a collection with a given merkle root
+///
+/// The levels are ordered from the leaf to the root.
+#[derive(Canon, Clone, Debug, PartialEq)]
+pub struct MerkleProof<D> {
+    /// The levels of the proof
+    pub levels: Vec<ProofLevel<D>>,
+}
+
+impl<'a, C, A> Branch<'a, C, A>
+where
+    C: Compound<A>,
+    C::Leaf: Canon,
+    A: Combine<C, A>,
+{
+    /// Returns a proof of inclusion of the leaf pointed at by the branch
+    pub fn proof<H>(&self) -> MerkleProof<H::Digest>
+    where
+        A: Borrow<MerkleRoot<H>>,
+        H: MerkleHasher,
+    {
+        let mut levels = vec![];
+
+        for level in self.levels().iter().rev() {
+            let ofs = level.offset();
+            let mut proof_level = ProofLevel {
+                before: vec![],
+                after: vec![],
+            };
+
+            for i in 0.. {
+                let digest = match level.child(i) {
+                    Child::Leaf(l) => Some(MerkleRoot::<H>::of_leaf(l).0),
+                    Child::Node(n) => {
+                        let root: &MerkleRoot<H> = n.annotation().borrow();
+                        Some(root.0.clone())
+                    }
+                    Child::Empty => None,
+                    Child::EndOfNode => break,
+                };
+
+                if i < ofs {
+                    proof_level.before.push(digest)
+                } else if i > ofs {
+                    proof_level.after.push(digest)
+                }
+            }
+
+            levels.push(proof_level);
+        }
+
+        MerkleProof { levels }
+    }
+}
+
+/// Verifies that `proof` shows the inclusion of `leaf` in a collection with
+/// merkle root `root`
+pub fn verify_proof<H, L>(
+    root: &MerkleRoot<H>,
+    leaf: &L,
+    proof: &MerkleProof<H::Digest>,
+) -> bool
+where
+    H: MerkleHasher,
+    L: Canon,
+{
+    let mut current = MerkleRoot::<H>::of_leaf(leaf);
+
+    for level in &proof.levels {
+        let children = level
+            .before
+            .iter()
+            .map(Option::as_ref)
+            .chain(iter::once(Some(&current.0)))
+            .chain(level.after.iter().map(Option::as_ref));
+
+        current = MerkleRoot::of_node(children);
+    }
+
+    current == *root
+}
@@ -0,0 +1,183 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+//
+// Copyright (c) DUSK NETWORK. All rights reserved.
+
+/// Annotation to keep track of the smallest element of a collection
+use core::borrow::Borrow;
+use core::cmp::Ordering;
+use core::marker::PhantomData;
+
+use canonical::{Canon, CanonError};
+use canonical_derive::Canon;
+
+use crate::annotations::{Annotation, Combine, Keyed};
+use crate::branch::Branch;
+use crate::branch_mut::BranchMut;
+use crate::compound::{Child, Compound, MutableLeaves};
+use crate::walk::{Step, Walk, Walker};
+
+/// The minimum value of a collection
+#[derive(Canon, PartialEq, Eq, Debug, Clone, Copy, Default)]
+pub enum MinKey<K> {
+    /// Identity of min, everything else is smaller
+    #[default]
+    PositiveInfinity,
+    /// Actual min value
+    Minimum(K),
+}
+
+impl<K> PartialOrd for MinKey<K>
+where
+    K: PartialOrd,
+{
+    fn partial_cmp(&self, other: &MinKey<K>) -> Option<Ordering> {
+        match (self, other) {
+            (MinKey::PositiveInfinity, MinKey::PositiveInfinity) => {
+                Some(Ordering::Equal)
+            }
+            (_, MinKey::PositiveInfinity) => Some(Ordering::Less),
+            (MinKey::PositiveInfinity, _) => Some(Ordering::Greater),
+            (MinKey::Minimum(a), MinKey::Minimum(b)) => a.partial_cmp(b),
+        }
+    }
+}
+
+impl<K> Ord for MinKey<K>
+where
+    K: Ord,
+{
+    fn cmp(&self, other: &MinKey<K>) -> Ordering {
+        match (self, other) {
+            (MinKey::PositiveInfinity, MinKey::PositiveInfinity) => {
+                Ordering::Equal
+            }
+            (_, MinKey::PositiveInfinity) => Ordering::Less,
+            (MinKey::PositiveInfinity, _) => Ordering::Greater,
+            (MinKey::Minimum(a), MinKey::Minimum(b)) => a.cmp(b),
+        }
+    }
+}
+
+impl<K, L> Annotation<L> for MinKey<K>
+where
+    L: Keyed<K>,
+    K: Clone,
+{
+    fn from_leaf(leaf: &L) -> Self {
+        MinKey::Minimum(leaf.key().clone())
+    }
+}
+
+impl<C, A, K> Combine<C, A> for MinKey<K>
+where
+    C: Compound<A>,
+    C::Leaf: Keyed<K>,
+    A: Annotation<C::Leaf> + Borrow<Self>,
+    K: Ord + Clone,
+{
+    fn combine(node: &C) -> Self {
+        let mut min = MinKey::PositiveInfinity;
+
+        for child in node.children() {
+            let ann = &*child.annotation();
+            let m = ann.borrow();
+
+            if *m < min {
+                min = m.clone()
+            }
+        }
+        min
+    }
+}
+
+/// Walker to find the minimum key in the collection
+pub struct FindMinKey<K>(PhantomData<K>);
+
+impl<K> Default for FindMinKey<K> {
+    fn default() -> Self {
+        FindMinKey(PhantomData)
+    }
+}
+
+impl<C, A, K> Walker<C, A> for FindMinKey<K>
+where
+    C: Compound<A>,
+    C::Leaf: Keyed<K>,
+    A: Combine<C, A> + Borrow<MinKey<K>>,
+    K: Ord + Clone + core::fmt::Debug,
+{
+    fn walk(&mut self, walk: Walk<C, A>) -> Step {
+        let mut current_min: MinKey<K> = MinKey::PositiveInfinity;
+        let mut current_step = Step::Abort;
+
+        for i in 0.. {
+            match walk.child(i) {
+                Child::Leaf(l) => {
+                    let leaf_min: MinKey<K> = MinKey::Minimum(l.key().clone());
+
+                    if leaf_min < current_min {
+                        current_min = leaf_min;
+                        current_step = Step::Found(i);
+                    }
+                }
+                Child::Node(n) => {
+                    let node_min = n.annotation().borrow().clone();
+                    if node_min < current_min {
+                        current_min = node_min;
+                        current_step = Step::Into(i);
+                    }
+                }
+                Child::Empty => (),
+                Child::EndOfNode => return current_step,
+            }
+        }
+        unreachable!()
+    }
+}
+
+/// Trait that provides a min_key() method to any Compound with a MinKey
+/// annotation
+pub trait GetMinKey<'a, A, K>
+where
+    Self: Compound<A>,
+    Self::Leaf: Keyed<K>,
+    A: Combine<Self, A> + Borrow<MinKey<K>> + Clone,
+    K: Ord,
+{
+    /// Construct a `Branch` pointing to the element with the smallest key
+    fn min_key(&'a self) -> Result<Option<Branch<'a, Self, A>>, CanonError>;
+
+    /// Construct a `BranchMut` pointing to the element with the smallest key
+    fn min_key_mut(
+        &'a mut self,
+    ) -> Result<Option<BranchMut<'a, Self, A>>, CanonError>
+    where
+        A: Combine<Self, A>,
+        Self: MutableLeaves;
+}
+
+impl<'a, C, A, K> GetMinKey<'a, A, K> for C
+where
+    C: Compound<A> + Clone,
+    C::Leaf: Keyed<K>,
+    A: Combine<C, A> + Borrow<MinKey<K>>,
+    K: Ord + Clone + core::fmt::Debug,
+{
+    fn min_key(&'a self) -> Result<Option<Branch<'a, Self, A>>, CanonError> {
+        // Return the first that satisfies the walk
+        Branch::<_, A>::walk(self, FindMinKey::default())
+    }
+
+    fn min_key_mut(
+        &'a mut self,
+    ) -> Result<Option<BranchMut<'a, Self, A>>, CanonError>
+    where
+        A: Combine<Self, A>,
+        C: MutableLeaves,
+    {
+        // Return the first mutable branch that satisfies the walk
+        BranchMut::<_, A>::walk(self, FindMinKey::default())
+    }
+}
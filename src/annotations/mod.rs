@@ -4,22 +4,41 @@
 //
 // Copyright (c) DUSK NETWORK. All rights reserved.
 
+use core::cell::{Ref, RefMut};
 use core::ops::{Deref, DerefMut};
 
-use canonical::{CanonError, Repr, Val, ValMut};
-use canonical_derive::Canon;
+use canonical::{Canon, CanonError, Sink, Source};
 
 use crate::compound::Compound;
+use crate::link::Link;
 
 use alloc::rc::Rc;
 
+mod byte_len;
 mod cardinality;
+mod consistency;
 mod max_key;
+mod merkle;
+mod min_key;
+mod range;
+mod sum;
+mod top_k;
+mod tuple;
 mod unit;
 
 // re-exports
+pub use byte_len::{ByteLen, ByteOffset, NthByte};
+pub(crate) use cardinality::{leaves_before, Offset};
 pub use cardinality::{Cardinality, Nth};
-pub use max_key::{GetMaxKey, Keyed, MaxKey};
+pub use consistency::{verify_annotations, AnnotationMismatch};
+pub use max_key::{FindKey, FindMaxKey, GetKey, GetMaxKey, Keyed, MaxKey};
+pub use merkle::{
+    verify_proof, Blake2b, MerkleHasher, MerkleProof, MerkleRoot, ProofLevel,
+};
+pub use min_key::{FindMinKey, GetMinKey, MinKey};
+pub use range::{GetRange, KeyRange};
+pub use sum::{Sum, Weighted, WeightedNth};
+pub use top_k::{BottomK, TopK};
 
 /// The trait defining an annotation type over a leaf
 pub trait Annotation<Leaf>: Default + Clone {
@@ -40,7 +59,14 @@ where
 /// Reference to an annotated value, along with it annotation
 pub struct AnnRef<'a, C, A> {
     annotation: &'a A,
-    val: Val<'a, C>,
+    val: Ref<'a, C>,
+}
+
+impl<'a, C, A> AnnRef<'a, C, A> {
+    /// Returns a reference to the cached annotation
+    pub fn annotation(&self) -> &A {
+        self.annotation
+    }
 }
 
 impl<'a, C, A> Deref for AnnRef<'a, C, A>
@@ -51,7 +77,7 @@ where
     type Target = C;
 
     fn deref(&self) -> &Self::Target {
-        &*self.val
+        &self.val
     }
 }
 
@@ -84,7 +110,8 @@ where
     A: Combine<C, A>,
 {
     annotation: &'a mut A,
-    val: ValMut<'a, C>,
+    val: RefMut<'a, C>,
+    recombine: bool,
 }
 
 impl<'a, C, A> AnnRefMut<'a, C, A>
@@ -95,6 +122,11 @@ where
     pub fn annotation(&self) -> &A {
         self.annotation
     }
+
+    /// Keeps the cached annotation on drop, for nodes left unchanged
+    pub(crate) fn keep_annotation(&mut self) {
+        self.recombine = false
+    }
 }
 
 impl<'a, C, A> Deref for AnnRefMut<'a, C, A>
@@ -105,7 +137,7 @@ where
     type Target = C;
 
     fn deref(&self) -> &Self::Target {
-        &*self.val
+        &self.val
     }
 }
 
@@ -125,13 +157,15 @@ where
     A: Combine<C, A>,
 {
     fn drop(&mut self) {
-        *self.annotation = A::combine(&*self.val)
+        if self.recombine {
+            *self.annotation = A::combine(&*self.val)
+        }
     }
 }
 
-#[derive(Debug, Canon)]
+#[derive(Debug)]
 /// A wrapper type that keeps the annotation of the Compound referenced cached
-pub struct Annotated<C, A>(Repr<C>, Rc<A>);
+pub struct Annotated<C, A>(Link<C>, Rc<A>);
 
 impl<C, A> Clone for Annotated<C, A> {
     fn clone(&self) -> Self {
@@ -139,6 +173,25 @@ impl<C, A> Clone for Annotated<C, A> {
     }
 }
 
+impl<C, A> Canon for Annotated<C, A>
+where
+    C: Canon,
+    A: Canon,
+{
+    fn encode(&self, sink: &mut Sink) {
+        self.0.encode(sink);
+        self.1.encode(sink);
+    }
+
+    fn decode(source: &mut Source) -> Result<Self, CanonError> {
+        Ok(Annotated(Link::decode(source)?, Rc::decode(source)?))
+    }
+
+    fn encoded_len(&self) -> usize {
+        self.0.encoded_len() + self.1.encoded_len()
+    }
+}
+
 impl<C, A> Annotated<C, A>
 where
     C: Compound<A>,
@@ -150,7 +203,7 @@ where
         A: Combine<C, A>,
     {
         let a = A::combine(&compound);
-        Annotated(Repr::new(compound), Rc::new(a))
+        Annotated(Link::new(compound), Rc::new(a))
     }
 
     /// Returns a reference to to the annotation stored
@@ -159,21 +212,33 @@ where
     }
 
     /// Returns an annotated reference to the underlying type
-    pub fn val(&self) -> Result<AnnRef<C, A>, CanonError> {
+    pub fn val(&self) -> Result<AnnRef<'_, C, A>, CanonError> {
+        let val = self.0.val()?;
         Ok(AnnRef {
-            val: self.0.val()?,
+            val,
             annotation: &self.1,
         })
     }
 
     /// Returns a Mutable annotated reference to the underlying type
-    pub fn val_mut(&mut self) -> Result<AnnRefMut<C, A>, CanonError>
+    pub fn val_mut(&mut self) -> Result<AnnRefMut<'_, C, A>, CanonError>
     where
         A: Combine<C, A>,
     {
+        let val = self.0.val_mut()?;
         Ok(AnnRefMut {
             annotation: Rc::make_mut(&mut self.1),
-            val: self.0.val_mut()?,
+            val,
+            recombine: true,
         })
     }
+
+    /// Takes the value out of the annotated, leaving a default value, if it
+    /// is held in memory and not shared with any clone
+    pub(crate) fn take_unique(&mut self) -> Option<C>
+    where
+        C: Default,
+    {
+        self.0.take_unique()
+    }
 }
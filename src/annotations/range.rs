@@ -0,0 +1,156 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+//
+// Copyright (c) DUSK NETWORK. All rights reserved.
+
+/// Iteration over the leaves of a sorted collection with keys in a range
+use core::borrow::Borrow;
+use core::marker::PhantomData;
+use core::ops::{Bound, RangeBounds};
+
+use canonical::CanonError;
+
+use crate::annotations::{Combine, Keyed, MaxKey};
+use crate::branch::BranchIterator;
+use crate::branch_mut::BranchMutIterator;
+use crate::compound::{Child, Compound, MutableLeaves, SortedLeaves};
+use crate::walk::{Direction, Step, Walk, Walker};
+
+/// Walker visiting the leaves of a sorted collection with keys in a range
+pub struct KeyRange<R, K> {
+    range: R,
+    _marker: PhantomData<K>,
+}
+
+impl<R, K> KeyRange<R, K>
+where
+    R: RangeBounds<K>,
+    K: Ord,
+{
+    /// Creates a walker visiting the leaves with keys in `range`
+    pub fn new(range: R) -> Self {
+        KeyRange {
+            range,
+            _marker: PhantomData,
+        }
+    }
+
+    fn below_start(&self, key: &K) -> bool {
+        match self.range.start_bound() {
+            Bound::Included(start) => key < start,
+            Bound::Excluded(start) => key <= start,
+            Bound::Unbounded => false,
+        }
+    }
+
+    fn above_end(&self, key: &K) -> bool {
+        match self.range.end_bound() {
+            Bound::Included(end) => key > end,
+            Bound::Excluded(end) => key >= end,
+            Bound::Unbounded => false,
+        }
+    }
+}
+
+impl<C, A, R, K> Walker<C, A> for KeyRange<R, K>
+where
+    C: Compound<A> + SortedLeaves,
+    C::Leaf: Keyed<K>,
+    A: Combine<C, A> + Borrow<MaxKey<K>>,
+    R: RangeBounds<K>,
+    K: Ord,
+{
+    fn walk(&mut self, walk: Walk<C, A>) -> Step {
+        let backward = walk.direction() == Direction::Backward;
+        for i in 0.. {
+            match walk.child(i) {
+                Child::Leaf(l) => {
+                    let key = l.key();
+                    // Since the leaves are sorted, we're done once past the
+                    // range in the direction of the walk
+                    if backward {
+                        if self.below_start(key) {
+                            return Step::Abort;
+                        } else if !self.above_end(key) {
+                            return Step::Found(i);
+                        }
+                    } else if self.above_end(key) {
+                        return Step::Abort;
+                    } else if !self.below_start(key) {
+                        return Step::Found(i);
+                    }
+                }
+                Child::Node(n) => {
+                    // Skip subtrees with all keys below the range
+                    if let MaxKey::Maximum(max) = n.annotation().borrow() {
+                        if !self.below_start(max) {
+                            return Step::Into(i);
+                        } else if backward {
+                            return Step::Abort;
+                        }
+                    }
+                }
+                Child::Empty => (),
+                Child::EndOfNode => return Step::Advance,
+            }
+        }
+        unreachable!()
+    }
+}
+
+/// Trait that provides `range()` and `range_mut()` methods to any sorted
+/// Compound with a MaxKey annotation
+pub trait GetRange<'a, A, K>
+where
+    Self: Compound<A> + SortedLeaves,
+    Self::Leaf: Keyed<K>,
+    A: Combine<Self, A> + Borrow<MaxKey<K>>,
+    K: Ord,
+{
+    /// Returns an iterator over the elements with keys in `range`
+    fn range<R>(
+        &'a self,
+        range: R,
+    ) -> Result<BranchIterator<'a, Self, A, KeyRange<R, K>>, CanonError>
+    where
+        R: RangeBounds<K>;
+
+    /// Returns a mutable iterator over the elements with keys in `range`
+    fn range_mut<R>(
+        &'a mut self,
+        range: R,
+    ) -> Result<BranchMutIterator<'a, Self, A, KeyRange<R, K>>, CanonError>
+    where
+        R: RangeBounds<K>,
+        Self: MutableLeaves;
+}
+
+impl<'a, C, A, K> GetRange<'a, A, K> for C
+where
+    C: Compound<A> + SortedLeaves + Clone,
+    C::Leaf: Keyed<K>,
+    A: Combine<C, A> + Borrow<MaxKey<K>>,
+    K: Ord,
+{
+    fn range<R>(
+        &'a self,
+        range: R,
+    ) -> Result<BranchIterator<'a, Self, A, KeyRange<R, K>>, CanonError>
+    where
+        R: RangeBounds<K>,
+    {
+        Ok(BranchIterator::new(self, KeyRange::new(range)))
+    }
+
+    fn range_mut<R>(
+        &'a mut self,
+        range: R,
+    ) -> Result<BranchMutIterator<'a, Self, A, KeyRange<R, K>>, CanonError>
+    where
+        R: RangeBounds<K>,
+        C: MutableLeaves,
+    {
+        Ok(BranchMutIterator::new(self, KeyRange::new(range)))
+    }
+}
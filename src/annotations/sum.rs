@@ -0,0 +1,165 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+//
+// Copyright (c) DUSK NETWORK. All rights reserved.
+
+/// Annotation to keep track of the sum of the weights of the elements in a
+/// collection
+use core::borrow::Borrow;
+use core::ops::{Add, Sub};
+
+use canonical::CanonError;
+use canonical_derive::Canon;
+
+use crate::annotations::{Annotation, Combine};
+use crate::branch::Branch;
+use crate::branch_mut::BranchMut;
+use crate::compound::{Child, Compound, MutableLeaves};
+use crate::walk::{Step, Walk, Walker};
+
+/// The sum of the weights of a compound collection
+#[derive(Canon, PartialEq, Debug, Clone, Default, Copy)]
+pub struct Sum<T>(pub(crate) T);
+
+impl<T> Sum<T> {
+    /// Returns a reference to the summed value
+    pub fn value(&self) -> &T {
+        &self.0
+    }
+}
+
+/// Trait for getting the weight from a Leaf value
+pub trait Weighted<T> {
+    /// Return a reference to the weight of the leaf type
+    fn weight(&self) -> &T;
+}
+
+// Elements can be their own weights
+impl<T> Weighted<T> for T {
+    fn weight(&self) -> &T {
+        self
+    }
+}
+
+impl<T, L> Annotation<L> for Sum<T>
+where
+    L: Weighted<T>,
+    T: Default + Clone,
+{
+    fn from_leaf(leaf: &L) -> Self {
+        Sum(leaf.weight().clone())
+    }
+}
+
+impl<C, A, T> Combine<C, A> for Sum<T>
+where
+    C: Compound<A>,
+    C::Leaf: Weighted<T>,
+    A: Annotation<C::Leaf> + Borrow<Self>,
+    T: Default + Clone + Add<Output = T>,
+{
+    fn combine(node: &C) -> Self {
+        let mut sum = T::default();
+        for child in node.children() {
+            let ann = &*child.annotation();
+            let s: &Sum<T> = ann.borrow();
+            sum = sum + s.0.clone();
+        }
+        Sum(sum)
+    }
+}
+
+/// Walker method to find the element in which a cumulative weight falls
+pub struct WeightOffset<T>(T);
+
+impl<C, A, T> Walker<C, A> for WeightOffset<T>
+where
+    C: Compound<A>,
+    C::Leaf: Weighted<T>,
+    A: Combine<C, A> + Borrow<Sum<T>>,
+    T: PartialOrd + Clone + Sub<Output = T>,
+{
+    fn walk(&mut self, walk: Walk<C, A>) -> Step {
+        for i in 0.. {
+            match walk.child(i) {
+                Child::Leaf(l) => {
+                    let weight = l.weight();
+
+                    if *weight <= self.0 {
+                        self.0 = self.0.clone() - weight.clone();
+                    } else {
+                        return Step::Found(i);
+                    }
+                }
+                Child::Node(node) => {
+                    let sum: &Sum<T> = node.annotation().borrow();
+
+                    if sum.0 <= self.0 {
+                        self.0 = self.0.clone() - sum.0.clone();
+                    } else {
+                        return Step::Into(i);
+                    }
+                }
+                Child::Empty => (),
+                Child::EndOfNode => return Step::Abort,
+            }
+        }
+        unreachable!()
+    }
+}
+
+/// Trait that provides `weighted_nth()` and `weighted_nth_mut()` methods to
+/// any Compound with a Sum annotation
+pub trait WeightedNth<'a, A, T>
+where
+    Self: Compound<A>,
+    Self::Leaf: Weighted<T>,
+    A: Combine<Self, A> + Borrow<Sum<T>>,
+{
+    /// Construct a `Branch` pointing to the element containing the cumulative
+    /// weight `w`, if any
+    ///
+    /// Element `i` covers the weights from the sum of all weights before it,
+    /// inclusive, up to that sum plus its own weight, exclusive.
+    fn weighted_nth(
+        &'a self,
+        w: T,
+    ) -> Result<Option<Branch<'a, Self, A>>, CanonError>;
+
+    /// Construct a `BranchMut` pointing to the element containing the
+    /// cumulative weight `w`, if any
+    fn weighted_nth_mut(
+        &'a mut self,
+        w: T,
+    ) -> Result<Option<BranchMut<'a, Self, A>>, CanonError>
+    where
+        Self: MutableLeaves;
+}
+
+impl<'a, C, A, T> WeightedNth<'a, A, T> for C
+where
+    C: Compound<A> + Clone,
+    C::Leaf: Weighted<T>,
+    A: Combine<C, A> + Borrow<Sum<T>>,
+    T: PartialOrd + Clone + Sub<Output = T>,
+{
+    fn weighted_nth(
+        &'a self,
+        w: T,
+    ) -> Result<Option<Branch<'a, Self, A>>, CanonError> {
+        // Return the first that satisfies the walk
+        Branch::<_, A>::walk(self, WeightOffset(w))
+    }
+
+    fn weighted_nth_mut(
+        &'a mut self,
+        w: T,
+    ) -> Result<Option<BranchMut<'a, Self, A>>, CanonError>
+    where
+        C: MutableLeaves,
+    {
+        // Return the first mutable branch that satisfies the walk
+        BranchMut::<_, A>::walk(self, WeightOffset(w))
+    }
+}
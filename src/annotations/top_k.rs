@@ -0,0 +1,211 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+//
+// Copyright (c) DUSK NETWORK. All rights reserved.
+
+/// Best-first searches for the leaves with the largest or smallest keys
+use core::borrow::Borrow;
+use core::cmp::{Ordering, Reverse};
+
+use alloc::collections::BinaryHeap;
+use alloc::vec::Vec;
+
+use canonical::CanonError;
+
+use crate::annotations::{Annotated, Combine, Keyed, MaxKey, MinKey};
+use crate::compound::{Child, Compound};
+
+enum Candidate<'a, C, A>
+where
+    C: Compound<A>,
+{
+    Leaf(&'a C::Leaf),
+    Node(&'a Annotated<C, A>),
+}
+
+/// A leaf or a subtree, ordered by the best key it can contain
+struct Ranked<'a, C, A, O>
+where
+    C: Compound<A>,
+{
+    rank: O,
+    candidate: Candidate<'a, C, A>,
+}
+
+impl<'a, C, A, O> PartialEq for Ranked<'a, C, A, O>
+where
+    C: Compound<A>,
+    O: Ord,
+{
+    fn eq(&self, other: &Self) -> bool {
+        self.rank == other.rank
+    }
+}
+
+impl<'a, C, A, O> Eq for Ranked<'a, C, A, O>
+where
+    C: Compound<A>,
+    O: Ord,
+{
+}
+
+impl<'a, C, A, O> PartialOrd for Ranked<'a, C, A, O>
+where
+    C: Compound<A>,
+    O: Ord,
+{
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl<'a, C, A, O> Ord for Ranked<'a, C, A, O>
+where
+    C: Compound<A>,
+    O: Ord,
+{
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.rank.cmp(&other.rank)
+    }
+}
+
+/// Collects the `k` leaves with the highest rank, expanding the subtrees in
+/// order of the best rank they can contain.
+///
+/// `node_rank` returns `None` for subtrees without leaves.
+fn best_first<'a, C, A, O, N, L>(
+    root: &'a C,
+    k: usize,
+    node_rank: N,
+    leaf_rank: L,
+) -> Result<Vec<&'a C::Leaf>, CanonError>
+where
+    C: Compound<A>,
+    C::Leaf: 'a,
+    A: Combine<C, A> + 'a,
+    O: Ord,
+    N: Fn(&'a A) -> Option<O>,
+    L: Fn(&'a C::Leaf) -> O,
+{
+    let mut heap = BinaryHeap::new();
+    let mut found = vec![];
+
+    let expand = |heap: &mut BinaryHeap<_>, node: &'a C| {
+        for i in 0.. {
+            match node.child(i) {
+                Child::Leaf(l) => heap.push(Ranked {
+                    rank: leaf_rank(l),
+                    candidate: Candidate::Leaf(l),
+                }),
+                Child::Node(n) => {
+                    if let Some(rank) = node_rank(n.annotation()) {
+                        heap.push(Ranked {
+                            rank,
+                            candidate: Candidate::Node(n),
+                        })
+                    }
+                }
+                Child::Empty => (),
+                Child::EndOfNode => break,
+            }
+        }
+    };
+
+    expand(&mut heap, root);
+
+    while found.len() < k {
+        match heap.pop() {
+            Some(Ranked {
+                candidate: Candidate::Leaf(l),
+                ..
+            }) => found.push(l),
+            Some(Ranked {
+                candidate: Candidate::Node(n),
+                ..
+            }) => {
+                let node = n.val()?;
+                // Extend the lifetime of the node.
+                //
+                // The node lives behind an `Rc` owned by the tree, which is
+                // borrowed for `'a` and cannot change in the meantime.
+                // See comment in `Branch::walk` for the full justification.
+                let node: &'a C = unsafe { &*(&*node as *const C) };
+                expand(&mut heap, node);
+            }
+            None => break,
+        }
+    }
+
+    Ok(found)
+}
+
+/// Trait that provides a `top_k()` method to any Compound with a MaxKey
+/// annotation
+pub trait TopK<'a, A, K>
+where
+    Self: Compound<A>,
+    Self::Leaf: Keyed<K>,
+    A: Combine<Self, A> + Borrow<MaxKey<K>>,
+    K: Ord,
+{
+    /// Returns the `k` leaves with the largest keys, in descending key order
+    ///
+    /// Only the subtrees that can contain one of them are loaded.
+    fn top_k(&'a self, k: usize) -> Result<Vec<&'a Self::Leaf>, CanonError>;
+}
+
+impl<'a, C, A, K> TopK<'a, A, K> for C
+where
+    C: Compound<A>,
+    C::Leaf: Keyed<K>,
+    A: Combine<C, A> + Borrow<MaxKey<K>> + 'a,
+    K: Ord + 'a,
+{
+    fn top_k(&'a self, k: usize) -> Result<Vec<&'a Self::Leaf>, CanonError> {
+        best_first(
+            self,
+            k,
+            |a: &'a A| match a.borrow() {
+                MaxKey::Maximum(max) => Some(max),
+                MaxKey::NegativeInfinity => None,
+            },
+            |l: &'a C::Leaf| l.key(),
+        )
+    }
+}
+
+/// Trait that provides a `bottom_k()` method to any Compound with a MinKey
+/// annotation
+pub trait BottomK<'a, A, K>
+where
+    Self: Compound<A>,
+    Self::Leaf: Keyed<K>,
+    A: Combine<Self, A> + Borrow<MinKey<K>>,
+    K: Ord,
+{
+    /// Returns the `k` leaves with the smallest keys, in ascending key order
+    ///
+    /// Only the subtrees that can contain one of them are loaded.
+    fn bottom_k(&'a self, k: usize) -> Result<Vec<&'a Self::Leaf>, CanonError>;
+}
+
+impl<'a, C, A, K> BottomK<'a, A, K> for C
+where
+    C: Compound<A>,
+    C::Leaf: Keyed<K>,
+    A: Combine<C, A> + Borrow<MinKey<K>> + 'a,
+    K: Ord + 'a,
+{
+    fn bottom_k(&'a self, k: usize) -> Result<Vec<&'a Self::Leaf>, CanonError> {
+        best_first(
+            self,
+            k,
+            |a: &'a A| match a.borrow() {
+                MinKey::Minimum(min) => Some(Reverse(min)),
+                MinKey::PositiveInfinity => None,
+            },
+            |l: &'a C::Leaf| Reverse(l.key()),
+        )
+    }
+}
@@ -0,0 +1,228 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+//
+// Copyright (c) DUSK NETWORK. All rights reserved.
+
+//! Pairs and triples of annotations are annotations themselves, combining each
+//! member independently.
+//!
+//! Those made up of distinct annotations from this crate can additionally be
+//! borrowed as any of their members, allowing traits such as `Nth` or
+//! `GetMaxKey` to be used directly on collections annotated with them. Larger
+//! compositions, or ones including annotations from other crates, are better
+//! served by deriving `Annotation` for a struct, which can be borrowed as any
+//! of its fields.
+
+use core::borrow::Borrow;
+
+use crate::annotations::{
+    Annotation, ByteLen, Cardinality, Combine, MaxKey, MerkleHasher,
+    MerkleRoot, MinKey, Sum,
+};
+use crate::compound::Compound;
+
+macro_rules! tuple {
+    ( $($name:ident)+ ) => {
+        impl<L, $($name,)+> Annotation<L> for ($($name,)+)
+        where
+            $($name: Annotation<L>,)+
+        {
+            fn from_leaf(leaf: &L) -> Self {
+                ($($name::from_leaf(leaf),)+)
+            }
+        }
+
+        impl<C, A, $($name,)+> Combine<C, A> for ($($name,)+)
+        where
+            C: Compound<A>,
+            $($name: Combine<C, A>,)+
+        {
+            fn combine(node: &C) -> Self {
+                ($($name::combine(node),)+)
+            }
+        }
+    };
+}
+
+tuple! { A0 A1 }
+tuple! { A0 A1 A2 }
+
+// Since `Borrow` is a foreign trait, it cannot be implemented for a member at
+// one position while leaving the others generic, as tuples repeating it would
+// get conflicting implementations. The tuples of distinct annotations of this
+// crate are listed instead, each borrowed as the member at every position.
+macro_rules! borrow_members {
+    ( @tuple [$($gen:tt)*] ($m0:ty, $m1:ty) ) => {
+        borrow_members!(@at [$($gen)*] ($m0, $m1), 0, $m0);
+        borrow_members!(@at [$($gen)*] ($m0, $m1), 1, $m1);
+    };
+    ( @tuple [$($gen:tt)*] ($m0:ty, $m1:ty, $m2:ty) ) => {
+        borrow_members!(@at [$($gen)*] ($m0, $m1, $m2), 0, $m0);
+        borrow_members!(@at [$($gen)*] ($m0, $m1, $m2), 1, $m1);
+        borrow_members!(@at [$($gen)*] ($m0, $m1, $m2), 2, $m2);
+    };
+    ( @at [$($gen:tt)*] $tuple:ty, $idx:tt, $member:ty ) => {
+        impl<$($gen)*> Borrow<$member> for $tuple {
+            fn borrow(&self) -> &$member {
+                &self.$idx
+            }
+        }
+    };
+    ( $( [$($gen:tt)*] $tuple:tt; )+ ) => {
+        $(borrow_members!(@tuple [$($gen)*] $tuple);)+
+    };
+}
+
+borrow_members! {
+    [] (ByteLen, Cardinality);
+    [K] (ByteLen, MaxKey<K>);
+    [J] (ByteLen, MinKey<J>);
+    [T] (ByteLen, Sum<T>);
+    [H: MerkleHasher] (ByteLen, MerkleRoot<H>);
+    [] (Cardinality, ByteLen);
+    [K] (Cardinality, MaxKey<K>);
+    [J] (Cardinality, MinKey<J>);
+    [T] (Cardinality, Sum<T>);
+    [H: MerkleHasher] (Cardinality, MerkleRoot<H>);
+    [K] (MaxKey<K>, ByteLen);
+    [K] (MaxKey<K>, Cardinality);
+    [K, J] (MaxKey<K>, MinKey<J>);
+    [K, T] (MaxKey<K>, Sum<T>);
+    [K, H: MerkleHasher] (MaxKey<K>, MerkleRoot<H>);
+    [J] (MinKey<J>, ByteLen);
+    [J] (MinKey<J>, Cardinality);
+    [J, K] (MinKey<J>, MaxKey<K>);
+    [J, T] (MinKey<J>, Sum<T>);
+    [J, H: MerkleHasher] (MinKey<J>, MerkleRoot<H>);
+    [T] (Sum<T>, ByteLen);
+    [T] (Sum<T>, Cardinality);
+    [T, K] (Sum<T>, MaxKey<K>);
+    [T, J] (Sum<T>, MinKey<J>);
+    [T, H: MerkleHasher] (Sum<T>, MerkleRoot<H>);
+    [H: MerkleHasher] (MerkleRoot<H>, ByteLen);
+    [H: MerkleHasher] (MerkleRoot<H>, Cardinality);
+    [H: MerkleHasher, K] (MerkleRoot<H>, MaxKey<K>);
+    [H: MerkleHasher, J] (MerkleRoot<H>, MinKey<J>);
+    [H: MerkleHasher, T] (MerkleRoot<H>, Sum<T>);
+    [K] (ByteLen, Cardinality, MaxKey<K>);
+    [J] (ByteLen, Cardinality, MinKey<J>);
+    [T] (ByteLen, Cardinality, Sum<T>);
+    [H: MerkleHasher] (ByteLen, Cardinality, MerkleRoot<H>);
+    [K] (ByteLen, MaxKey<K>, Cardinality);
+    [K, J] (ByteLen, MaxKey<K>, MinKey<J>);
+    [K, T] (ByteLen, MaxKey<K>, Sum<T>);
+    [K, H: MerkleHasher] (ByteLen, MaxKey<K>, MerkleRoot<H>);
+    [J] (ByteLen, MinKey<J>, Cardinality);
+    [J, K] (ByteLen, MinKey<J>, MaxKey<K>);
+    [J, T] (ByteLen, MinKey<J>, Sum<T>);
+    [J, H: MerkleHasher] (ByteLen, MinKey<J>, MerkleRoot<H>);
+    [T] (ByteLen, Sum<T>, Cardinality);
+    [T, K] (ByteLen, Sum<T>, MaxKey<K>);
+    [T, J] (ByteLen, Sum<T>, MinKey<J>);
+    [T, H: MerkleHasher] (ByteLen, Sum<T>, MerkleRoot<H>);
+    [H: MerkleHasher] (ByteLen, MerkleRoot<H>, Cardinality);
+    [H: MerkleHasher, K] (ByteLen, MerkleRoot<H>, MaxKey<K>);
+    [H: MerkleHasher, J] (ByteLen, MerkleRoot<H>, MinKey<J>);
+    [H: MerkleHasher, T] (ByteLen, MerkleRoot<H>, Sum<T>);
+    [K] (Cardinality, ByteLen, MaxKey<K>);
+    [J] (Cardinality, ByteLen, MinKey<J>);
+    [T] (Cardinality, ByteLen, Sum<T>);
+    [H: MerkleHasher] (Cardinality, ByteLen, MerkleRoot<H>);
+    [K] (Cardinality, MaxKey<K>, ByteLen);
+    [K, J] (Cardinality, MaxKey<K>, MinKey<J>);
+    [K, T] (Cardinality, MaxKey<K>, Sum<T>);
+    [K, H: MerkleHasher] (Cardinality, MaxKey<K>, MerkleRoot<H>);
+    [J] (Cardinality, MinKey<J>, ByteLen);
+    [J, K] (Cardinality, MinKey<J>, MaxKey<K>);
+    [J, T] (Cardinality, MinKey<J>, Sum<T>);
+    [J, H: MerkleHasher] (Cardinality, MinKey<J>, MerkleRoot<H>);
+    [T] (Cardinality, Sum<T>, ByteLen);
+    [T, K] (Cardinality, Sum<T>, MaxKey<K>);
+    [T, J] (Cardinality, Sum<T>, MinKey<J>);
+    [T, H: MerkleHasher] (Cardinality, Sum<T>, MerkleRoot<H>);
+    [H: MerkleHasher] (Cardinality, MerkleRoot<H>, ByteLen);
+    [H: MerkleHasher, K] (Cardinality, MerkleRoot<H>, MaxKey<K>);
+    [H: MerkleHasher, J] (Cardinality, MerkleRoot<H>, MinKey<J>);
+    [H: MerkleHasher, T] (Cardinality, MerkleRoot<H>, Sum<T>);
+    [K] (MaxKey<K>, ByteLen, Cardinality);
+    [K, J] (MaxKey<K>, ByteLen, MinKey<J>);
+    [K, T] (MaxKey<K>, ByteLen, Sum<T>);
+    [K, H: MerkleHasher] (MaxKey<K>, ByteLen, MerkleRoot<H>);
+    [K] (MaxKey<K>, Cardinality, ByteLen);
+    [K, J] (MaxKey<K>, Cardinality, MinKey<J>);
+    [K, T] (MaxKey<K>, Cardinality, Sum<T>);
+    [K, H: MerkleHasher] (MaxKey<K>, Cardinality, MerkleRoot<H>);
+    [K, J] (MaxKey<K>, MinKey<J>, ByteLen);
+    [K, J] (MaxKey<K>, MinKey<J>, Cardinality);
+    [K, J, T] (MaxKey<K>, MinKey<J>, Sum<T>);
+    [K, J, H: MerkleHasher] (MaxKey<K>, MinKey<J>, MerkleRoot<H>);
+    [K, T] (MaxKey<K>, Sum<T>, ByteLen);
+    [K, T] (MaxKey<K>, Sum<T>, Cardinality);
+    [K, T, J] (MaxKey<K>, Sum<T>, MinKey<J>);
+    [K, T, H: MerkleHasher] (MaxKey<K>, Sum<T>, MerkleRoot<H>);
+    [K, H: MerkleHasher] (MaxKey<K>, MerkleRoot<H>, ByteLen);
+    [K, H: MerkleHasher] (MaxKey<K>, MerkleRoot<H>, Cardinality);
+    [K, H: MerkleHasher, J] (MaxKey<K>, MerkleRoot<H>, MinKey<J>);
+    [K, H: MerkleHasher, T] (MaxKey<K>, MerkleRoot<H>, Sum<T>);
+    [J] (MinKey<J>, ByteLen, Cardinality);
+    [J, K] (MinKey<J>, ByteLen, MaxKey<K>);
+    [J, T] (MinKey<J>, ByteLen, Sum<T>);
+    [J, H: MerkleHasher] (MinKey<J>, ByteLen, MerkleRoot<H>);
+    [J] (MinKey<J>, Cardinality, ByteLen);
+    [J, K] (MinKey<J>, Cardinality, MaxKey<K>);
+    [J, T] (MinKey<J>, Cardinality, Sum<T>);
+    [J, H: MerkleHasher] (MinKey<J>, Cardinality, MerkleRoot<H>);
+    [J, K] (MinKey<J>, MaxKey<K>, ByteLen);
+    [J, K] (MinKey<J>, MaxKey<K>, Cardinality);
+    [J, K, T] (MinKey<J>, MaxKey<K>, Sum<T>);
+    [J, K, H: MerkleHasher] (MinKey<J>, MaxKey<K>, MerkleRoot<H>);
+    [J, T] (MinKey<J>, Sum<T>, ByteLen);
+    [J, T] (MinKey<J>, Sum<T>, Cardinality);
+    [J, T, K] (MinKey<J>, Sum<T>, MaxKey<K>);
+    [J, T, H: MerkleHasher] (MinKey<J>, Sum<T>, MerkleRoot<H>);
+    [J, H: MerkleHasher] (MinKey<J>, MerkleRoot<H>, ByteLen);
+    [J, H: MerkleHasher] (MinKey<J>, MerkleRoot<H>, Cardinality);
+    [J, H: MerkleHasher, K] (MinKey<J>, MerkleRoot<H>, MaxKey<K>);
+    [J, H: MerkleHasher, T] (MinKey<J>, MerkleRoot<H>, Sum<T>);
+    [T] (Sum<T>, ByteLen, Cardinality);
+    [T, K] (Sum<T>, ByteLen, MaxKey<K>);
+    [T, J] (Sum<T>, ByteLen, MinKey<J>);
+    [T, H: MerkleHasher] (Sum<T>, ByteLen, MerkleRoot<H>);
+    [T] (Sum<T>, Cardinality, ByteLen);
+    [T, K] (Sum<T>, Cardinality, MaxKey<K>);
+    [T, J] (Sum<T>, Cardinality, MinKey<J>);
+    [T, H: MerkleHasher] (Sum<T>, Cardinality, MerkleRoot<H>);
+    [T, K] (Sum<T>, MaxKey<K>, ByteLen);
+    [T, K] (Sum<T>, MaxKey<K>, Cardinality);
+    [T, K, J] (Sum<T>, MaxKey<K>, MinKey<J>);
+    [T, K, H: MerkleHasher] (Sum<T>, MaxKey<K>, MerkleRoot<H>);
+    [T, J] (Sum<T>, MinKey<J>, ByteLen);
+    [T, J] (Sum<T>, MinKey<J>, Cardinality);
+    [T, J, K] (Sum<T>, MinKey<J>, MaxKey<K>);
+    [T, J, H: MerkleHasher] (Sum<T>, MinKey<J>, MerkleRoot<H>);
+    [T, H: MerkleHasher] (Sum<T>, MerkleRoot<H>, ByteLen);
+    [T, H: MerkleHasher] (Sum<T>, MerkleRoot<H>, Cardinality);
+    [T, H: MerkleHasher, K] (Sum<T>, MerkleRoot<H>, MaxKey<K>);
+    [T, H: MerkleHasher, J] (Sum<T>, MerkleRoot<H>, MinKey<J>);
+    [H: MerkleHasher] (MerkleRoot<H>, ByteLen, Cardinality);
+    [H: MerkleHasher, K] (MerkleRoot<H>, ByteLen, MaxKey<K>);
+    [H: MerkleHasher, J] (MerkleRoot<H>, ByteLen, MinKey<J>);
+    [H: MerkleHasher, T] (MerkleRoot<H>, ByteLen, Sum<T>);
+    [H: MerkleHasher] (MerkleRoot<H>, Cardinality, ByteLen);
+    [H: MerkleHasher, K] (MerkleRoot<H>, Cardinality, MaxKey<K>);
+    [H: MerkleHasher, J] (MerkleRoot<H>, Cardinality, MinKey<J>);
+    [H: MerkleHasher, T] (MerkleRoot<H>, Cardinality, Sum<T>);
+    [H: MerkleHasher, K] (MerkleRoot<H>, MaxKey<K>, ByteLen);
+    [H: MerkleHasher, K] (MerkleRoot<H>, MaxKey<K>, Cardinality);
+    [H: MerkleHasher, K, J] (MerkleRoot<H>, MaxKey<K>, MinKey<J>);
+    [H: MerkleHasher, K, T] (MerkleRoot<H>, MaxKey<K>, Sum<T>);
+    [H: MerkleHasher, J] (MerkleRoot<H>, MinKey<J>, ByteLen);
+    [H: MerkleHasher, J] (MerkleRoot<H>, MinKey<J>, Cardinality);
+    [H: MerkleHasher, J, K] (MerkleRoot<H>, MinKey<J>, MaxKey<K>);
+    [H: MerkleHasher, J, T] (MerkleRoot<H>, MinKey<J>, Sum<T>);
+    [H: MerkleHasher, T] (MerkleRoot<H>, Sum<T>, ByteLen);
+    [H: MerkleHasher, T] (MerkleRoot<H>, Sum<T>, Cardinality);
+    [H: MerkleHasher, T, K] (MerkleRoot<H>, Sum<T>, MaxKey<K>);
+    [H: MerkleHasher, T, J] (MerkleRoot<H>, Sum<T>, MinKey<J>);
+}
@@ -4,6 +4,9 @@
 //
 // Copyright (c) DUSK NETWORK. All rights reserved.
 
+use core::cmp::Ordering;
+use core::iter::Rev;
+use core::mem;
 use core::ops::Deref;
 
 use alloc::vec::Vec;
@@ -12,7 +15,7 @@ use canonical::CanonError;
 
 use crate::annotations::{AnnRef, Combine};
 use crate::compound::{Child, Compound};
-use crate::walk::{AllLeaves, Step, Walk, Walker};
+use crate::walk::{last_offset, AllLeaves, Direction, Step, Walk, Walker};
 
 #[derive(Debug)]
 enum LevelNode<'a, C, A> {
@@ -34,7 +37,7 @@ where
     type Target = C;
 
     fn deref(&self) -> &Self::Target {
-        &*self.node
+        &self.node
     }
 }
 
@@ -61,10 +64,21 @@ impl<'a, C, A> Level<'a, C, A> {
     fn offset_mut(&mut self) -> &mut usize {
         &mut self.offset
     }
+
+    fn shift(&mut self, ofs: usize, direction: Direction) {
+        match direction {
+            Direction::Forward => self.offset += ofs,
+            Direction::Backward => self.offset -= ofs,
+        }
+    }
 }
 
 #[derive(Debug)]
-pub struct PartialBranch<'a, C, A>(Vec<Level<'a, C, A>>);
+pub struct PartialBranch<'a, C, A> {
+    levels: Vec<Level<'a, C, A>>,
+    // the shallowest level changed since the last `take_changed`
+    changed: usize,
+}
 
 impl<'a, C, A> Deref for LevelNode<'a, C, A>
 where
@@ -76,7 +90,7 @@ where
     fn deref(&self) -> &Self::Target {
         match self {
             LevelNode::Root(target) => target,
-            LevelNode::Val(val) => &**val,
+            LevelNode::Val(val) => val,
         }
     }
 }
@@ -86,19 +100,22 @@ where
     C: Compound<A>,
     A: Combine<C, A>,
 {
-    fn new(root: &'a C) -> Self {
-        PartialBranch(vec![Level::new_root(root)])
+    pub(crate) fn new(root: &'a C) -> Self {
+        PartialBranch {
+            levels: vec![Level::new_root(root)],
+            changed: 0,
+        }
     }
 
     pub fn depth(&self) -> usize {
-        self.0.len()
+        self.levels.len()
     }
 
-    pub fn levels(&self) -> &[Level<C, A>] {
-        &self.0
+    pub fn levels(&self) -> &[Level<'_, C, A>] {
+        &self.levels
     }
 
-    fn leaf(&self) -> Option<&C::Leaf> {
+    pub(crate) fn leaf(&self) -> Option<&C::Leaf> {
         let top = self.top();
         let ofs = top.offset();
 
@@ -108,28 +125,90 @@ where
         }
     }
 
-    fn top(&self) -> &Level<C, A> {
-        self.0.last().expect("Never empty")
+    fn top(&self) -> &Level<'_, C, A> {
+        self.levels.last().expect("Never empty")
     }
 
     fn top_mut(&mut self) -> &mut Level<'a, C, A> {
-        self.0.last_mut().expect("Never empty")
+        self.changed = self.changed.min(self.levels.len() - 1);
+        self.levels.last_mut().expect("Never empty")
+    }
+
+    /// Returns the shallowest level changed since the last call, the levels
+    /// above it keeping their offsets
+    pub(crate) fn take_changed(&mut self) -> usize {
+        mem::replace(&mut self.changed, self.levels.len())
+    }
+
+    /// Returns the offsets of the levels, from the root down
+    pub(crate) fn position(&self) -> Vec<usize> {
+        self.levels.iter().map(Level::offset).collect()
+    }
+
+    /// Overwrites `position` with the offsets of the levels, reusing its
+    /// allocation
+    pub(crate) fn save_position(&self, position: &mut Vec<usize>) {
+        position.clear();
+        position.extend(self.levels.iter().map(Level::offset));
+    }
+
+    /// Moves the branch one child in `direction`, unwinding any level it
+    /// moves back out of.
+    ///
+    /// Returns false if the branch moved back past the first child of the
+    /// root.
+    pub(crate) fn step(&mut self, direction: Direction) -> bool {
+        match direction {
+            Direction::Forward => {
+                *self.top_mut().offset_mut() += 1;
+                true
+            }
+            Direction::Backward => loop {
+                let top = self.top_mut();
+                if top.offset() > 0 {
+                    *top.offset_mut() -= 1;
+                    return true;
+                }
+                if self.pop().is_none() {
+                    return false;
+                }
+            },
+        }
     }
 
-    fn advance(&mut self) {
-        *self.top_mut().offset_mut() += 1;
+    /// Unwinds the branch to the root, at the first or last child depending
+    /// on `direction`
+    pub(crate) fn reset(&mut self, direction: Direction) {
+        while self.pop().is_some() {}
+        let top = self.top_mut();
+        *top.offset_mut() = match direction {
+            Direction::Forward => 0,
+            Direction::Backward => last_offset::<C, A>(top),
+        };
+    }
+
+    /// Unwinds the branch to `depth` levels, at the first child of the top
+    /// level
+    pub(crate) fn unwind_to(&mut self, depth: usize) {
+        while self.depth() > depth && self.pop().is_some() {}
+        *self.top_mut().offset_mut() = 0;
     }
 
     fn pop(&mut self) -> Option<Level<'a, C, A>> {
         // We never pop the root
-        if self.0.len() > 1 {
-            self.0.pop()
+        if self.levels.len() > 1 {
+            self.changed = self.changed.min(self.levels.len() - 1);
+            self.levels.pop()
         } else {
             None
         }
     }
 
-    fn walk<W>(&mut self, walker: &mut W) -> Result<Option<()>, CanonError>
+    pub(crate) fn walk<W>(
+        &mut self,
+        walker: &mut W,
+        direction: Direction,
+    ) -> Result<Option<()>, CanonError>
     where
         W: Walker<C, A>,
     {
@@ -143,29 +222,34 @@ where
         loop {
             match core::mem::replace(&mut state, State::Init) {
                 State::Init => (),
-                State::Push(push) => self.0.push(push),
-                State::Pop => match self.pop() {
-                    Some(_) => {
-                        self.advance();
+                State::Push(push) => self.levels.push(push),
+                State::Pop => {
+                    if self.pop().is_none() || !self.step(direction) {
+                        return Ok(None);
                     }
-                    None => return Ok(None),
-                },
+                }
             }
 
+            let depth = self.levels.len() - 1;
             let top = self.top_mut();
-            let step = walker.walk(Walk::new(&**top, top.offset()));
+            let step =
+                walker.walk(Walk::new(&**top, top.offset(), direction, depth));
 
             match step {
                 Step::Found(walk_ofs) => {
-                    *top.offset_mut() += walk_ofs;
+                    top.shift(walk_ofs, direction);
                     return Ok(Some(()));
                 }
                 Step::Into(walk_ofs) => {
-                    *top.offset_mut() += walk_ofs;
+                    top.shift(walk_ofs, direction);
                     let ofs = top.offset();
                     let top_child = top.child(ofs);
                     if let Child::Node(n) = top_child {
-                        let level: Level<'_, C, A> = Level::new_val(n.val()?);
+                        let mut level: Level<'_, C, A> =
+                            Level::new_val(n.val()?);
+                        if direction == Direction::Backward {
+                            *level.offset_mut() = last_offset::<C, A>(&level);
+                        }
                         // Extend the lifetime of the Level.
                         //
                         // JUSTIFICATION
@@ -219,17 +303,23 @@ where
         }
     }
 
-    fn path<P>(&mut self, mut path: P) -> Result<Option<()>, CanonError>
+    pub(crate) fn path<P>(
+        &mut self,
+        mut path: P,
+    ) -> Result<Option<()>, CanonError>
     where
-        P: FnMut() -> usize,
+        P: FnMut() -> Option<usize>,
     {
         let mut push = None;
         loop {
             if let Some(push) = push.take() {
-                self.0.push(push);
+                self.levels.push(push);
             }
 
-            let ofs = path();
+            let ofs = match path() {
+                Some(ofs) => ofs,
+                None => return Ok(None),
+            };
             let top = self.top_mut();
             *top.offset_mut() = ofs;
 
@@ -254,6 +344,31 @@ where
             }
         }
     }
+
+    /// Moves the branch to the leaf at `position`, keeping the levels shared
+    /// with the current position
+    pub(crate) fn seek(
+        &mut self,
+        position: &[usize],
+    ) -> Result<Option<()>, CanonError> {
+        let shared = self
+            .levels
+            .iter()
+            .zip(position)
+            .take_while(|(level, ofs)| level.offset() == **ofs)
+            .count();
+
+        let keep = (shared + 1).min(position.len());
+        while self.depth() > keep && self.pop().is_some() {}
+
+        match position.get(self.depth() - 1..) {
+            Some(rest) => {
+                let mut rest = rest.iter().copied();
+                self.path(|| rest.next())
+            }
+            None => Ok(None),
+        }
+    }
 }
 
 impl<'a, C, A> Branch<'a, C, A>
@@ -267,7 +382,7 @@ where
     }
 
     /// Returns a slice into the levels of the tree.
-    pub fn levels(&self) -> &[Level<C, A>] {
+    pub fn levels(&self) -> &[Level<'_, C, A>] {
         self.0.levels()
     }
 
@@ -293,16 +408,61 @@ where
         W: Walker<C, A>,
     {
         let mut partial = PartialBranch::new(root);
-        Ok(partial.walk(&mut walker)?.map(|()| Branch(partial)))
+        Ok(partial
+            .walk(&mut walker, Direction::Forward)?
+            .map(|()| Branch(partial)))
+    }
+
+    /// Performs a tree walk visiting the children of every node from the last
+    /// to the first, returning either a valid branch or None if the walk
+    /// failed.
+    pub fn walk_back<W>(
+        root: &'a C,
+        mut walker: W,
+    ) -> Result<Option<Self>, CanonError>
+    where
+        W: Walker<C, A>,
+    {
+        let mut partial = PartialBranch::new(root);
+        partial.reset(Direction::Backward);
+        Ok(partial
+            .walk(&mut walker, Direction::Backward)?
+            .map(|()| Branch(partial)))
     }
 
     /// Construct a branch given a function returning child offsets
-    pub fn path<P>(root: &'a C, path: P) -> Result<Option<Self>, CanonError>
+    pub fn path<P>(root: &'a C, mut path: P) -> Result<Option<Self>, CanonError>
     where
         P: FnMut() -> usize,
     {
         let mut partial = PartialBranch::new(root);
-        Ok(partial.path(path)?.map(|()| Branch(partial)))
+        Ok(partial.path(|| Some(path()))?.map(|()| Branch(partial)))
+    }
+
+    /// Returns an iterator over the leaves starting with the one pointed
+    /// at by the branch, using `walker` to find the following ones
+    pub fn iter_with<W>(self, walker: W) -> BranchIterator<'a, C, A, W>
+    where
+        W: Walker<C, A>,
+    {
+        BranchIterator::from_partial(
+            self.0,
+            walker,
+            Direction::Forward,
+            End::At,
+        )
+    }
+
+    /// Returns an iterator over the leaves from the one pointed at by the
+    /// branch back to the first leaf of the collection
+    pub fn into_iter_rev(self) -> Rev<BranchIterator<'a, C, A, AllLeaves>> {
+        BranchIterator::from_partial(
+            self.0,
+            AllLeaves,
+            Direction::Backward,
+            End::At,
+        )
+        .rev()
     }
 }
 
@@ -311,7 +471,7 @@ where
 /// Branche are always guaranteed to point at a leaf, and can be dereferenced
 /// to the pointed-at leaf.
 #[derive(Debug)]
-pub struct Branch<'a, C, A>(PartialBranch<'a, C, A>);
+pub struct Branch<'a, C, A>(pub(crate) PartialBranch<'a, C, A>);
 
 impl<'a, C, A> Deref for Branch<'a, C, A>
 where
@@ -325,6 +485,7 @@ where
     }
 }
 
+/// A `Branch` with a mapped leaf
 pub struct MappedBranch<'a, C, A, M>
 where
     C: Compound<A>,
@@ -347,10 +508,190 @@ where
     }
 }
 
-pub enum BranchIterator<'a, C, A, W> {
-    Initial(Branch<'a, C, A>, W),
-    Intermediate(Branch<'a, C, A>, W),
-    Exhausted,
+/// One end of an iterator over the leaves of a collection
+#[derive(Debug, Clone, Copy)]
+pub(crate) enum End {
+    /// The end of the collection
+    Open,
+    /// At the next leaf to be yielded
+    At,
+    /// At the last leaf yielded
+    Past,
+}
+
+/// The end of an iterator opposite to the one its branch is at
+#[derive(Debug)]
+pub(crate) struct FarEnd {
+    pub(crate) end: End,
+    pub(crate) position: Vec<usize>,
+    // the number of leading offsets shared with the branch when last compared
+    shared: usize,
+}
+
+impl FarEnd {
+    /// Creates a far end at the given position
+    pub(crate) fn new(end: End, position: Vec<usize>) -> Self {
+        FarEnd {
+            end,
+            position,
+            shared: 0,
+        }
+    }
+
+    /// Returns true if a leaf reached moving in `direction` from the other
+    /// end lies beyond this one.
+    ///
+    /// The leaf is given by the `offset` of each of the `depth` levels of its
+    /// branch, where only the levels from `changed` down can differ from the
+    /// last comparison, so only those are compared again.
+    pub(crate) fn excludes<O>(
+        &mut self,
+        changed: usize,
+        depth: usize,
+        offset: O,
+        direction: Direction,
+    ) -> bool
+    where
+        O: Fn(usize) -> usize,
+    {
+        let beyond = match direction {
+            Direction::Forward => Ordering::Greater,
+            Direction::Backward => Ordering::Less,
+        };
+
+        let len = depth.min(self.position.len());
+        self.shared = self.shared.min(changed).min(len);
+        while self.shared < len
+            && offset(self.shared) == self.position[self.shared]
+        {
+            self.shared += 1;
+        }
+
+        let ord = if self.shared < len {
+            offset(self.shared).cmp(&self.position[self.shared])
+        } else {
+            depth.cmp(&self.position.len())
+        };
+
+        match self.end {
+            End::Open => false,
+            End::At => ord == beyond,
+            End::Past => ord != beyond.reverse(),
+        }
+    }
+}
+
+/// Iterator over the leaves of a collection, starting at a branch and using a
+/// `Walker` to find the next leaf
+///
+/// The iterator is double-ended, using the same `Walker` to find the leaves
+/// from the end of the collection, visiting the children of nodes backward.
+pub struct BranchIterator<'a, C, A, W> {
+    // `None` once exhausted
+    branch: Option<PartialBranch<'a, C, A>>,
+    walker: W,
+    // the end the branch is at, advanced in place
+    live: Direction,
+    near: End,
+    far: FarEnd,
+}
+
+impl<'a, C, A, W> BranchIterator<'a, C, A, W>
+where
+    C: Compound<A>,
+    A: Combine<C, A>,
+    W: Walker<C, A>,
+{
+    /// Creates an iterator over the leaves of `root` found by `walker`
+    ///
+    /// The walk starts on the first call to `next` or `next_back`.
+    pub fn new(root: &'a C, walker: W) -> Self {
+        Self::from_partial(
+            PartialBranch::new(root),
+            walker,
+            Direction::Forward,
+            End::Open,
+        )
+    }
+
+    fn from_partial(
+        branch: PartialBranch<'a, C, A>,
+        walker: W,
+        live: Direction,
+        near: End,
+    ) -> Self {
+        BranchIterator {
+            branch: Some(branch),
+            walker,
+            live,
+            near,
+            far: FarEnd::new(End::Open, Vec::new()),
+        }
+    }
+
+    /// Yields the next leaf from the front or from the back
+    fn step(
+        &mut self,
+        direction: Direction,
+    ) -> Option<Result<&'a C::Leaf, CanonError>> {
+        let branch = self.branch.as_mut()?;
+
+        // Switching ends leaves the position of the branch behind, and
+        // moves it to the position left behind by the last switch
+        let (end, found) = if direction == self.live {
+            (self.near, Ok(Some(())))
+        } else {
+            let far = FarEnd::new(self.near, branch.position());
+            let far = mem::replace(&mut self.far, far);
+            self.live = direction;
+            match far.end {
+                End::Open => (End::Open, Ok(Some(()))),
+                end => (end, branch.seek(&far.position)),
+            }
+        };
+
+        let found = match (end, found) {
+            (End::Open, _) => {
+                branch.reset(direction);
+                branch.walk(&mut self.walker, direction)
+            }
+            (End::Past, Ok(Some(()))) => {
+                if branch.step(direction) {
+                    branch.walk(&mut self.walker, direction)
+                } else {
+                    Ok(None)
+                }
+            }
+            (_, found) => found,
+        };
+
+        match found {
+            Ok(Some(())) => {
+                let changed = branch.take_changed();
+                let levels = branch.levels();
+                if !self.far.excludes(
+                    changed,
+                    levels.len(),
+                    |i| levels[i].offset(),
+                    direction,
+                ) {
+                    self.near = End::Past;
+                    let leaf: &C::Leaf = branch.leaf().expect("Invalid branch");
+                    let leaf_extended: &'a C::Leaf =
+                        unsafe { core::mem::transmute(leaf) };
+                    return Some(Ok(leaf_extended));
+                }
+            }
+            Ok(None) => (),
+            Err(e) => {
+                self.branch = None;
+                return Some(Err(e));
+            }
+        }
+
+        self.branch = None;
+        None
+    }
 }
 
 // iterators
@@ -364,7 +705,7 @@ where
     type IntoIter = BranchIterator<'a, C, A, AllLeaves>;
 
     fn into_iter(self) -> Self::IntoIter {
-        BranchIterator::Initial(self, AllLeaves)
+        self.iter_with(AllLeaves)
     }
 }
 
@@ -378,39 +719,17 @@ where
     type Item = Result<&'a C::Leaf, CanonError>;
 
     fn next(&mut self) -> Option<Self::Item> {
-        match core::mem::replace(self, BranchIterator::Exhausted) {
-            BranchIterator::Initial(branch, walker) => {
-                *self = BranchIterator::Intermediate(branch, walker);
-            }
-            BranchIterator::Intermediate(mut branch, mut walker) => {
-                branch.0.advance();
-                // access partialbranch
-                match branch.0.walk(&mut walker) {
-                    Ok(None) => {
-                        *self = BranchIterator::Exhausted;
-                        return None;
-                    }
-                    Ok(Some(..)) => {
-                        *self = BranchIterator::Intermediate(branch, walker);
-                    }
-                    Err(e) => {
-                        return Some(Err(e));
-                    }
-                }
-            }
-            BranchIterator::Exhausted => {
-                return None;
-            }
-        }
+        self.step(Direction::Forward)
+    }
+}
 
-        match self {
-            BranchIterator::Intermediate(branch, _) => {
-                let leaf: &C::Leaf = &*branch;
-                let leaf_extended: &'a C::Leaf =
-                    unsafe { core::mem::transmute(leaf) };
-                Some(Ok(leaf_extended))
-            }
-            _ => unreachable!(),
-        }
+impl<'a, C, A, W> DoubleEndedIterator for BranchIterator<'a, C, A, W>
+where
+    C: Compound<A>,
+    A: Combine<C, A>,
+    W: Walker<C, A>,
+{
+    fn next_back(&mut self) -> Option<Self::Item> {
+        self.step(Direction::Backward)
     }
 }
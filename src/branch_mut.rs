@@ -4,6 +4,7 @@
 //
 // Copyright (c) DUSK NETWORK. All rights reserved.
 
+use core::iter::Rev;
 use core::mem;
 use core::ops::{Deref, DerefMut};
 
@@ -12,8 +13,11 @@ use alloc::vec::Vec;
 use canonical::CanonError;
 
 use crate::annotations::{AnnRefMut, Combine};
-use crate::compound::{Child, ChildMut, Compound};
-use crate::walk::{AllLeaves, Step, Walk, Walker};
+use crate::branch::{End, FarEnd};
+use crate::compound::{
+    Child, ChildMut, Compound, CompoundInsert, CompoundRemove, OwnedChild,
+};
+use crate::walk::{last_offset, AllLeaves, Direction, Step, Walk, Walker};
 
 #[derive(Debug)]
 enum LevelNodeMut<'a, C, A>
@@ -35,7 +39,7 @@ where
     fn deref(&self) -> &Self::Target {
         match self {
             LevelNodeMut::Root(target) => target,
-            LevelNodeMut::Val(val) => &**val,
+            LevelNodeMut::Val(val) => val,
         }
     }
 }
@@ -48,7 +52,7 @@ where
     fn deref_mut(&mut self) -> &mut Self::Target {
         match self {
             LevelNodeMut::Root(target) => target,
-            LevelNodeMut::Val(val) => &mut **val,
+            LevelNodeMut::Val(val) => val,
         }
     }
 }
@@ -61,6 +65,8 @@ where
 {
     offset: usize,
     node: LevelNodeMut<'a, C, A>,
+    // set once a leaf or a child node of the level is changed
+    dirty: bool,
 }
 
 impl<'a, C, A> Deref for LevelMut<'a, C, A>
@@ -71,7 +77,7 @@ where
     type Target = C;
 
     fn deref(&self) -> &Self::Target {
-        &*self.node
+        &self.node
     }
 }
 
@@ -81,7 +87,7 @@ where
     A: Combine<C, A>,
 {
     fn deref_mut(&mut self) -> &mut Self::Target {
-        &mut *self.node
+        &mut self.node
     }
 }
 
@@ -94,6 +100,7 @@ where
         LevelMut {
             offset: 0,
             node: LevelNodeMut::Root(root),
+            dirty: false,
         }
     }
 
@@ -101,6 +108,7 @@ where
         LevelMut {
             offset: 0,
             node: LevelNodeMut::Val(ann),
+            dirty: false,
         }
     }
 
@@ -112,35 +120,70 @@ where
     fn offset_mut(&mut self) -> &mut usize {
         &mut self.offset
     }
+
+    fn shift(&mut self, ofs: usize, direction: Direction) {
+        match direction {
+            Direction::Forward => self.offset += ofs,
+            Direction::Backward => self.offset -= ofs,
+        }
+    }
 }
 
-pub struct PartialBranchMut<'a, C, A>(Vec<LevelMut<'a, C, A>>)
+pub struct PartialBranchMut<'a, C, A>
 where
     C: Compound<A>,
-    A: Combine<C, A>;
+    A: Combine<C, A>,
+{
+    levels: Vec<LevelMut<'a, C, A>>,
+    // the shallowest level changed since the last `take_changed`
+    changed: usize,
+    // whether to keep the cached annotations of the levels left clean
+    lazy: bool,
+}
 
 impl<'a, C, A> PartialBranchMut<'a, C, A>
 where
     C: Compound<A>,
     A: Combine<C, A>,
 {
-    fn new(root: &'a mut C) -> Self {
-        PartialBranchMut(vec![LevelMut::new_root(root)])
+    pub(crate) fn new(root: &'a mut C) -> Self {
+        PartialBranchMut {
+            levels: vec![LevelMut::new_root(root)],
+            changed: 0,
+            lazy: false,
+        }
+    }
+
+    /// Only recombines the annotations of the levels below which a leaf was
+    /// changed from now on, as `CursorMut` does
+    pub(crate) fn recombine_lazily(&mut self) {
+        self.lazy = true;
     }
 
     pub fn depth(&self) -> usize {
-        self.0.len()
+        self.levels.len()
+    }
+
+    pub fn levels(&self) -> &[LevelMut<'a, C, A>] {
+        &self.levels
     }
 
-    fn top(&self) -> &LevelMut<C, A> {
-        self.0.last().expect("Never empty")
+    fn top(&self) -> &LevelMut<'_, C, A> {
+        self.levels.last().expect("Never empty")
     }
 
     fn top_mut(&mut self) -> &mut LevelMut<'a, C, A> {
-        self.0.last_mut().expect("Never empty")
+        self.changed = self.changed.min(self.levels.len() - 1);
+        self.levels.last_mut().expect("Never empty")
     }
 
-    fn leaf(&self) -> Option<&C::Leaf> {
+    /// Returns the shallowest level changed since the last call, the levels
+    /// above it keeping their offsets
+    pub(crate) fn take_changed(&mut self) -> usize {
+        mem::replace(&mut self.changed, self.levels.len())
+    }
+
+    pub(crate) fn leaf(&self) -> Option<&C::Leaf> {
         let top = self.top();
         let ofs = top.offset();
 
@@ -150,9 +193,10 @@ where
         }
     }
 
-    fn leaf_mut(&mut self) -> Option<&mut C::Leaf> {
+    pub(crate) fn leaf_mut(&mut self) -> Option<&mut C::Leaf> {
         let top = self.top_mut();
         let ofs = top.offset();
+        top.dirty = true;
 
         match top.child_mut(ofs) {
             ChildMut::Leaf(l) => Some(l),
@@ -160,20 +204,86 @@ where
         }
     }
 
-    fn advance(&mut self) {
-        *self.top_mut().offset_mut() += 1
+    /// Returns the offsets of the levels, from the root down
+    pub(crate) fn position(&self) -> Vec<usize> {
+        self.levels.iter().map(LevelMut::offset).collect()
     }
 
-    fn pop(&mut self) -> Option<LevelMut<'a, C, A>> {
-        // We never pop the root
-        if self.0.len() > 1 {
-            self.0.pop()
-        } else {
-            None
+    /// Overwrites `position` with the offsets of the levels, reusing its
+    /// allocation
+    pub(crate) fn save_position(&self, position: &mut Vec<usize>) {
+        position.clear();
+        position.extend(self.levels.iter().map(LevelMut::offset));
+    }
+
+    /// Moves the branch one child in `direction`, unwinding any level it
+    /// moves back out of.
+    ///
+    /// Returns false if the branch moved back past the first child of the
+    /// root.
+    pub(crate) fn step(&mut self, direction: Direction) -> bool {
+        match direction {
+            Direction::Forward => {
+                *self.top_mut().offset_mut() += 1;
+                true
+            }
+            Direction::Backward => loop {
+                let top = self.top_mut();
+                if top.offset() > 0 {
+                    *top.offset_mut() -= 1;
+                    return true;
+                }
+                if !self.pop() {
+                    return false;
+                }
+            },
         }
     }
 
-    fn walk<W>(&mut self, walker: &mut W) -> Result<Option<()>, CanonError>
+    /// Unwinds the branch to the root, at the first or last child depending
+    /// on `direction`
+    pub(crate) fn reset(&mut self, direction: Direction) {
+        while self.pop() {}
+        let top = self.top_mut();
+        *top.offset_mut() = match direction {
+            Direction::Forward => 0,
+            Direction::Backward => last_offset::<C, A>(top),
+        };
+    }
+
+    /// Pops the top level, recombining its annotation unless the branch is
+    /// lazy and the level clean. A dirty level in turn dirties its parent.
+    ///
+    /// Returns false if the top level is the root, which is never popped.
+    fn pop(&mut self) -> bool {
+        if self.levels.len() == 1 {
+            return false;
+        }
+
+        let mut level = self.levels.pop().expect("Never empty");
+        self.changed = self.changed.min(self.levels.len());
+        if level.dirty {
+            self.top_mut().dirty = true;
+        } else if self.lazy {
+            if let LevelNodeMut::Val(ann) = &mut level.node {
+                ann.keep_annotation();
+            }
+        }
+        true
+    }
+
+    /// Unwinds the branch to `depth` levels, at the first child of the top
+    /// level
+    pub(crate) fn unwind_to(&mut self, depth: usize) {
+        while self.depth() > depth && self.pop() {}
+        *self.top_mut().offset_mut() = 0;
+    }
+
+    pub(crate) fn walk<W>(
+        &mut self,
+        walker: &mut W,
+        direction: Direction,
+    ) -> Result<Option<()>, CanonError>
     where
         W: Walker<C, A>,
     {
@@ -187,30 +297,34 @@ where
         loop {
             match mem::replace(&mut state, State::Init) {
                 State::Init => (),
-                State::Push(push) => self.0.push(push),
-                State::Pop => match self.pop() {
-                    Some(_) => {
-                        self.advance();
+                State::Push(push) => self.levels.push(push),
+                State::Pop => {
+                    if !self.pop() || !self.step(direction) {
+                        return Ok(None);
                     }
-                    None => return Ok(None),
-                },
+                }
             }
 
+            let depth = self.levels.len() - 1;
             let top = self.top_mut();
-            let step = walker.walk(Walk::new(&**top, top.offset()));
+            let step =
+                walker.walk(Walk::new(&**top, top.offset(), direction, depth));
 
             match step {
                 Step::Found(walk_ofs) => {
-                    *top.offset_mut() += walk_ofs;
+                    top.shift(walk_ofs, direction);
                     return Ok(Some(()));
                 }
                 Step::Into(walk_ofs) => {
-                    *top.offset_mut() += walk_ofs;
+                    top.shift(walk_ofs, direction);
                     let ofs = top.offset();
                     let top_child = top.child_mut(ofs);
                     if let ChildMut::Node(n) = top_child {
-                        let level: LevelMut<'_, C, A> =
+                        let mut level: LevelMut<'_, C, A> =
                             LevelMut::new_val(n.val_mut()?);
+                        if direction == Direction::Backward {
+                            *level.offset_mut() = last_offset::<C, A>(&level);
+                        }
 
                         // Extend the lifetime of the Level.
                         // See comment in `Branch::walk` for justification.
@@ -227,17 +341,23 @@ where
         }
     }
 
-    fn path<P>(&mut self, mut path: P) -> Result<Option<()>, CanonError>
+    pub(crate) fn path<P>(
+        &mut self,
+        mut path: P,
+    ) -> Result<Option<()>, CanonError>
     where
-        P: FnMut() -> usize,
+        P: FnMut() -> Option<usize>,
     {
         let mut push = None;
         loop {
             if let Some(push) = push.take() {
-                self.0.push(push);
+                self.levels.push(push);
             }
 
-            let ofs = path();
+            let ofs = match path() {
+                Some(ofs) => ofs,
+                None => return Ok(None),
+            };
             let top = self.top_mut();
             *top.offset_mut() = ofs;
 
@@ -263,16 +383,41 @@ where
             }
         }
     }
+
+    /// Moves the branch to the leaf at `position`, keeping the levels shared
+    /// with the current position
+    pub(crate) fn seek(
+        &mut self,
+        position: &[usize],
+    ) -> Result<Option<()>, CanonError> {
+        let shared = self
+            .levels
+            .iter()
+            .zip(position)
+            .take_while(|(level, ofs)| level.offset() == **ofs)
+            .count();
+
+        let keep = (shared + 1).min(position.len());
+        while self.depth() > keep && self.pop() {}
+
+        match position.get(self.depth() - 1..) {
+            Some(rest) => {
+                let mut rest = rest.iter().copied();
+                self.path(|| rest.next())
+            }
+            None => Ok(None),
+        }
+    }
 }
 
-impl<'a, C, A> Drop for BranchMut<'a, C, A>
+impl<'a, C, A> Drop for PartialBranchMut<'a, C, A>
 where
     C: Compound<A>,
     A: Combine<C, A>,
 {
     fn drop(&mut self) {
-        // unwind when dropping
-        while self.0.pop().is_some() {}
+        // unwind when dropping, recombining the annotations
+        while self.pop() {}
     }
 }
 
@@ -286,6 +431,11 @@ where
         self.0.depth()
     }
 
+    /// Returns a slice into the levels of the tree.
+    pub(crate) fn levels(&self) -> &[LevelMut<'a, C, A>] {
+        self.0.levels()
+    }
+
     /// Returns a branch that maps the leaf to a specific value.
     /// Used in maps for example, to get easy access to the value of the KV-pair
     pub fn map_leaf<M>(
@@ -304,9 +454,11 @@ where
         self,
         closure: for<'b> fn(&'b mut C::Leaf) -> &'b mut M,
     ) -> BranchMutMappedMut<'a, C, A, M> {
+        let mut inner = self;
+        let mapped: *mut M = closure(&mut *inner);
         BranchMutMappedMut {
-            inner: self,
-            closure,
+            _inner: inner,
+            mapped,
         }
     }
 
@@ -320,16 +472,98 @@ where
         W: Walker<C, A>,
     {
         let mut partial = PartialBranchMut::new(root);
-        Ok(partial.walk(&mut walker)?.map(|()| BranchMut(partial)))
+        Ok(partial
+            .walk(&mut walker, Direction::Forward)?
+            .map(|()| BranchMut(partial)))
+    }
+
+    /// Performs a tree walk visiting the children of every node from the last
+    /// to the first, returning either a valid branch or None if the walk
+    /// failed.
+    pub fn walk_back<W>(
+        root: &'a mut C,
+        mut walker: W,
+    ) -> Result<Option<Self>, CanonError>
+    where
+        W: Walker<C, A>,
+    {
+        let mut partial = PartialBranchMut::new(root);
+        partial.reset(Direction::Backward);
+        Ok(partial
+            .walk(&mut walker, Direction::Backward)?
+            .map(|()| BranchMut(partial)))
     }
 
     /// Construct a branch given a function returning child offsets
-    pub fn path<P>(root: &'a mut C, path: P) -> Result<Option<Self>, CanonError>
+    pub fn path<P>(
+        root: &'a mut C,
+        mut path: P,
+    ) -> Result<Option<Self>, CanonError>
     where
         P: FnMut() -> usize,
     {
         let mut partial = PartialBranchMut::new(root);
-        Ok(partial.path(path)?.map(|()| BranchMut(partial)))
+        Ok(partial.path(|| Some(path()))?.map(|()| BranchMut(partial)))
+    }
+
+    /// Replaces the leaf pointed at by the branch, returning the old one
+    pub fn replace_leaf(&mut self, leaf: C::Leaf) -> C::Leaf {
+        mem::replace(self.0.leaf_mut().expect("Invalid branch"), leaf)
+    }
+
+    /// Removes the leaf pointed at by the branch from the collection,
+    /// recombining the annotations of the nodes on the branch
+    pub fn remove(mut self) -> Result<C::Leaf, CanonError>
+    where
+        C: CompoundRemove<A>,
+    {
+        let top = self.0.top_mut();
+        let ofs = top.offset();
+        top.dirty = true;
+
+        match top.remove_child(ofs)? {
+            OwnedChild::Leaf(leaf) => Ok(leaf),
+            _ => panic!("Invalid branch"),
+        }
+    }
+
+    /// Inserts `leaf` into the collection in front of the leaf pointed at by
+    /// the branch, recombining the annotations of the nodes on the branch
+    pub fn insert_before(mut self, leaf: C::Leaf) -> Result<(), CanonError>
+    where
+        C: CompoundInsert<A>,
+    {
+        let top = self.0.top_mut();
+        let ofs = top.offset();
+        top.dirty = true;
+
+        top.insert_leaf(ofs, leaf)
+    }
+
+    /// Returns an iterator over the mutable leaves starting with the one
+    /// pointed at by the branch, using `walker` to find the following ones
+    pub fn iter_mut_with<W>(self, walker: W) -> BranchMutIterator<'a, C, A, W>
+    where
+        W: Walker<C, A>,
+    {
+        BranchMutIterator::from_partial(
+            self.0,
+            walker,
+            Direction::Forward,
+            End::At,
+        )
+    }
+
+    /// Returns an iterator over the leaves from the one pointed at by the
+    /// branch back to the first leaf of the collection
+    pub fn into_iter_rev(self) -> Rev<BranchMutIterator<'a, C, A, AllLeaves>> {
+        BranchMutIterator::from_partial(
+            self.0,
+            AllLeaves,
+            Direction::Backward,
+            End::At,
+        )
+        .rev()
     }
 }
 
@@ -341,7 +575,7 @@ where
 ///
 /// Branches are always guaranteed to point at a leaf, and can be dereferenced
 /// to the pointed-at leaf.
-pub struct BranchMut<'a, C, A>(PartialBranchMut<'a, C, A>)
+pub struct BranchMut<'a, C, A>(pub(crate) PartialBranchMut<'a, C, A>)
 where
     C: Compound<A>,
     A: Combine<C, A>;
@@ -396,8 +630,13 @@ where
     C: Compound<A>,
     A: Combine<C, A>,
 {
-    inner: BranchMut<'a, C, A>,
-    closure: for<'b> fn(&'b mut C::Leaf) -> &'b mut M,
+    // Held for the borrow of the leaf, recombining annotations once dropped
+    _inner: BranchMut<'a, C, A>,
+    // Mapped once, since the mutable closure can't be called through a shared
+    // reference. The leaf lives in the nodes of the collection, not in the
+    // branch, so it does not move with it, and stays exclusively borrowed
+    // for as long as `_inner` lives.
+    mapped: *mut M,
 }
 
 impl<'a, C, A, M> Deref for BranchMutMappedMut<'a, C, A, M>
@@ -408,10 +647,7 @@ where
     type Target = M;
 
     fn deref(&self) -> &M {
-        // FIXME, could we just transmute &self to &mut self here, since we're
-        // turning it back to a & reference again directly?
-
-        todo!()
+        unsafe { &*self.mapped }
     }
 }
 
@@ -421,18 +657,126 @@ where
     A: Combine<C, A>,
 {
     fn deref_mut(&mut self) -> &mut M {
-        (self.closure)(&mut *self.inner)
+        unsafe { &mut *self.mapped }
     }
 }
 
-pub enum BranchMutIterator<'a, C, A, W>
+/// Iterator over mutable leaves of a collection, starting at a branch and
+/// using a `Walker` to find the next leaf
+///
+/// The iterator is double-ended, using the same `Walker` to find the leaves
+/// from the end of the collection, visiting the children of nodes backward.
+pub struct BranchMutIterator<'a, C, A, W>
 where
     C: Compound<A>,
     A: Combine<C, A>,
 {
-    Initial(BranchMut<'a, C, A>, W),
-    Intermediate(BranchMut<'a, C, A>, W),
-    Exhausted,
+    // `None` once exhausted
+    branch: Option<PartialBranchMut<'a, C, A>>,
+    walker: W,
+    // the end the branch is at, advanced in place
+    live: Direction,
+    near: End,
+    far: FarEnd,
+}
+
+impl<'a, C, A, W> BranchMutIterator<'a, C, A, W>
+where
+    C: Compound<A>,
+    A: Combine<C, A>,
+    W: Walker<C, A>,
+{
+    /// Creates an iterator over the leaves of `root` found by `walker`
+    ///
+    /// The walk starts on the first call to `next` or `next_back`.
+    pub fn new(root: &'a mut C, walker: W) -> Self {
+        Self::from_partial(
+            PartialBranchMut::new(root),
+            walker,
+            Direction::Forward,
+            End::Open,
+        )
+    }
+
+    fn from_partial(
+        branch: PartialBranchMut<'a, C, A>,
+        walker: W,
+        live: Direction,
+        near: End,
+    ) -> Self {
+        BranchMutIterator {
+            branch: Some(branch),
+            walker,
+            live,
+            near,
+            far: FarEnd::new(End::Open, Vec::new()),
+        }
+    }
+
+    /// Yields the next leaf from the front or from the back
+    fn step(
+        &mut self,
+        direction: Direction,
+    ) -> Option<Result<&'a mut C::Leaf, CanonError>> {
+        let branch = self.branch.as_mut()?;
+
+        // Switching ends leaves the position of the branch behind, and
+        // moves it to the position left behind by the last switch
+        let (end, found) = if direction == self.live {
+            (self.near, Ok(Some(())))
+        } else {
+            let far = FarEnd::new(self.near, branch.position());
+            let far = mem::replace(&mut self.far, far);
+            self.live = direction;
+            match far.end {
+                End::Open => (End::Open, Ok(Some(()))),
+                end => (end, branch.seek(&far.position)),
+            }
+        };
+
+        let found = match (end, found) {
+            (End::Open, _) => {
+                branch.reset(direction);
+                branch.walk(&mut self.walker, direction)
+            }
+            (End::Past, Ok(Some(()))) => {
+                if branch.step(direction) {
+                    branch.walk(&mut self.walker, direction)
+                } else {
+                    Ok(None)
+                }
+            }
+            (_, found) => found,
+        };
+
+        match found {
+            Ok(Some(())) => {
+                let changed = branch.take_changed();
+                let levels = branch.levels();
+                if !self.far.excludes(
+                    changed,
+                    levels.len(),
+                    |i| levels[i].offset(),
+                    direction,
+                ) {
+                    self.near = End::Past;
+                    let leaf: &mut C::Leaf =
+                        branch.leaf_mut().expect("Invalid branch");
+                    let leaf_extended: &'a mut C::Leaf =
+                        unsafe { core::mem::transmute(leaf) };
+                    return Some(Ok(leaf_extended));
+                }
+            }
+            Ok(None) => (),
+            Err(e) => {
+                self.branch = None;
+                return Some(Err(e));
+            }
+        }
+
+        self.branch = None;
+        None
+    }
 }
 
 // iterators
@@ -446,7 +790,7 @@ where
     type IntoIter = BranchMutIterator<'a, C, A, AllLeaves>;
 
     fn into_iter(self) -> Self::IntoIter {
-        BranchMutIterator::Initial(self, AllLeaves)
+        self.iter_mut_with(AllLeaves)
     }
 }
 
@@ -460,39 +804,17 @@ where
     type Item = Result<&'a mut C::Leaf, CanonError>;
 
     fn next(&mut self) -> Option<Self::Item> {
-        match core::mem::replace(self, BranchMutIterator::Exhausted) {
-            BranchMutIterator::Initial(branch, walker) => {
-                *self = BranchMutIterator::Intermediate(branch, walker);
-            }
-            BranchMutIterator::Intermediate(mut branch, mut walker) => {
-                branch.0.advance();
-                // access partialbranch
-                match branch.0.walk(&mut walker) {
-                    Ok(None) => {
-                        *self = BranchMutIterator::Exhausted;
-                        return None;
-                    }
-                    Ok(Some(..)) => {
-                        *self = BranchMutIterator::Intermediate(branch, walker);
-                    }
-                    Err(e) => {
-                        return Some(Err(e));
-                    }
-                }
-            }
-            BranchMutIterator::Exhausted => {
-                return None;
-            }
-        }
+        self.step(Direction::Forward)
+    }
+}
 
-        match self {
-            BranchMutIterator::Intermediate(branch, _) => {
-                let leaf: &mut C::Leaf = &mut *branch;
-                let leaf_extended: &'a mut C::Leaf =
-                    unsafe { core::mem::transmute(leaf) };
-                Some(Ok(leaf_extended))
-            }
-            _ => unreachable!(),
-        }
+impl<'a, C, A, W> DoubleEndedIterator for BranchMutIterator<'a, C, A, W>
+where
+    C: Compound<A>,
+    A: Combine<C, A>,
+    W: Walker<C, A>,
+{
+    fn next_back(&mut self) -> Option<Self::Item> {
+        self.step(Direction::Backward)
     }
 }
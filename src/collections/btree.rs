@@ -0,0 +1,330 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+//
+// Copyright (c) DUSK NETWORK. All rights reserved.
+
+use core::borrow::Borrow;
+use core::mem;
+use core::ops::RangeBounds;
+
+use alloc::vec::Vec;
+
+use canonical::{Canon, CanonError};
+use canonical_derive::Canon;
+
+use super::node::{
+    Balanced, Contents, ContentsOf, ContentsOfMut, OwnedContents, MAX,
+};
+use super::Entry;
+use crate::annotations::{
+    Annotated, Annotation, Combine, FindKey, KeyRange, MaxKey,
+};
+use crate::branch::{Branch, BranchIterator, MappedBranch};
+use crate::branch_mut::{BranchMut, BranchMutMappedMut};
+use crate::compound::{Child, ChildMut, Compound, SortedLeaves};
+
+// The minimum number of entries or children of a node other than the root
+const MIN: usize = MAX / 2;
+
+/// An ordered map, stored as a B-tree of `Annotated` nodes.
+///
+/// Entries are kept sorted by key in the bottom nodes, and the tree is
+/// descended using the `MaxKey` annotation of the subtrees. Clones share
+/// their nodes until one of them is changed.
+#[derive(Clone, Canon, Debug)]
+pub enum BTreeMap<K, V, A> {
+    /// Bottom node holding the entries
+    Entries(Vec<Entry<K, V>>),
+    /// Inner node holding subtrees of equal height
+    Nodes(Vec<Annotated<BTreeMap<K, V, A>, A>>),
+}
+
+impl<K, V, A> Default for BTreeMap<K, V, A> {
+    fn default() -> Self {
+        BTreeMap::Entries(Vec::new())
+    }
+}
+
+impl<K, V, A> Compound<A> for BTreeMap<K, V, A>
+where
+    K: Canon,
+    V: Canon,
+    A: Canon,
+{
+    type Leaf = Entry<K, V>;
+
+    fn child(&self, ofs: usize) -> Child<'_, Self, A>
+    where
+        A: Annotation<Self::Leaf>,
+    {
+        match self {
+            BTreeMap::Entries(entries) => match entries.get(ofs) {
+                Some(entry) => Child::Leaf(entry),
+                None => Child::EndOfNode,
+            },
+            BTreeMap::Nodes(nodes) => match nodes.get(ofs) {
+                Some(node) => Child::Node(node),
+                None => Child::EndOfNode,
+            },
+        }
+    }
+
+    fn child_mut(&mut self, ofs: usize) -> ChildMut<'_, Self, A>
+    where
+        A: Annotation<Self::Leaf>,
+    {
+        match self {
+            BTreeMap::Entries(entries) => match entries.get_mut(ofs) {
+                Some(entry) => ChildMut::Leaf(entry),
+                None => ChildMut::EndOfNode,
+            },
+            BTreeMap::Nodes(nodes) => match nodes.get_mut(ofs) {
+                Some(node) => ChildMut::Node(node),
+                None => ChildMut::EndOfNode,
+            },
+        }
+    }
+}
+
+impl<K, V, A> Balanced<A> for BTreeMap<K, V, A>
+where
+    K: Canon,
+    V: Canon,
+    A: Canon,
+{
+    type Item = Entry<K, V>;
+
+    fn contents(&self) -> ContentsOf<'_, Self, A> {
+        match self {
+            BTreeMap::Entries(entries) => Contents::Leaves(entries),
+            BTreeMap::Nodes(nodes) => Contents::Nodes(nodes),
+        }
+    }
+
+    fn contents_mut(&mut self) -> ContentsOfMut<'_, Self, A> {
+        match self {
+            BTreeMap::Entries(entries) => Contents::Leaves(entries),
+            BTreeMap::Nodes(nodes) => Contents::Nodes(nodes),
+        }
+    }
+
+    fn into_contents(self) -> OwnedContents<Self, A> {
+        match self {
+            BTreeMap::Entries(entries) => Contents::Leaves(entries),
+            BTreeMap::Nodes(nodes) => Contents::Nodes(nodes),
+        }
+    }
+
+    fn from_contents(contents: OwnedContents<Self, A>) -> Self {
+        match contents {
+            Contents::Leaves(entries) => BTreeMap::Entries(entries),
+            Contents::Nodes(nodes) => BTreeMap::Nodes(nodes),
+        }
+    }
+}
+
+impl<K, V, A> SortedLeaves for BTreeMap<K, V, A> {}
+
+impl<K, V, A> BTreeMap<K, V, A>
+where
+    K: Canon + Ord,
+    V: Canon,
+    A: Canon + Combine<Self, A> + Borrow<MaxKey<K>>,
+{
+    /// Creates a new empty map
+    pub fn new() -> Self {
+        Default::default()
+    }
+
+    /// Returns true if the map holds no entries
+    pub fn is_empty(&self) -> bool {
+        match self {
+            BTreeMap::Entries(entries) => entries.is_empty(),
+            BTreeMap::Nodes(nodes) => nodes.is_empty(),
+        }
+    }
+
+    /// Returns a branch to the value stored under `key`, if any
+    ///
+    /// Several branches into the same map can be held at once.
+    pub fn get(
+        &self,
+        key: &K,
+    ) -> Result<Option<MappedBranch<'_, Self, A, V>>, CanonError> {
+        Ok(Branch::walk(self, FindKey::new(key))?
+            .map(|branch| branch.map_leaf(Entry::value)))
+    }
+
+    /// Returns a mutable branch to the value stored under `key`, if any
+    ///
+    /// The annotations are recombined when the branch is dropped.
+    pub fn get_mut(
+        &mut self,
+        key: &K,
+    ) -> Result<Option<BranchMutMappedMut<'_, Self, A, V>>, CanonError> {
+        Ok(BranchMut::walk(self, FindKey::new(key))?
+            .map(|branch| branch.map_leaf_mut(Entry::value_mut)))
+    }
+
+    /// Returns an iterator over the entries with keys in `range`, in order
+    pub fn range<R>(
+        &self,
+        range: R,
+    ) -> BranchIterator<'_, Self, A, KeyRange<R, K>>
+    where
+        R: RangeBounds<K>,
+    {
+        BranchIterator::new(self, KeyRange::new(range))
+    }
+
+    /// Inserts `value` under `key`, returning the value previously stored
+    /// under it, if any
+    pub fn insert(
+        &mut self,
+        key: K,
+        value: V,
+    ) -> Result<Option<V>, CanonError> {
+        match self.insert_inner(Entry { key, value })? {
+            Insert::Replaced(old) => Ok(old),
+            Insert::Split(right) => {
+                // grow a new root above the split one
+                let left = mem::take(self);
+                *self = BTreeMap::Nodes(vec![
+                    Annotated::new(left),
+                    Annotated::new(right),
+                ]);
+                Ok(None)
+            }
+        }
+    }
+
+    fn insert_inner(
+        &mut self,
+        entry: Entry<K, V>,
+    ) -> Result<Insert<K, V, A>, CanonError> {
+        match self {
+            BTreeMap::Entries(entries) => {
+                match entries.binary_search_by(|e| e.key.cmp(&entry.key)) {
+                    Ok(i) => {
+                        let old =
+                            mem::replace(&mut entries[i].value, entry.value);
+                        return Ok(Insert::Replaced(Some(old)));
+                    }
+                    Err(i) => entries.insert(i, entry),
+                }
+            }
+            BTreeMap::Nodes(nodes) => {
+                let i =
+                    child_index(nodes, &entry.key).unwrap_or(nodes.len() - 1);
+
+                let inserted = nodes[i].val_mut()?.insert_inner(entry)?;
+                match inserted {
+                    Insert::Split(right) => {
+                        nodes.insert(i + 1, Annotated::new(right))
+                    }
+                    replaced => return Ok(replaced),
+                }
+            }
+        }
+
+        Ok(match self.split() {
+            Some(right) => Insert::Split(right),
+            None => Insert::Replaced(None),
+        })
+    }
+
+    /// Removes the entry with `key` from the map, returning its value, if
+    /// any
+    pub fn remove(&mut self, key: &K) -> Result<Option<V>, CanonError> {
+        // look the key up first, to leave nodes shared with clones alone
+        if self.get(key)?.is_none() {
+            return Ok(None);
+        }
+        let removed = self.remove_inner(key)?;
+        self.collapse()?;
+        Ok(removed)
+    }
+
+    fn remove_inner(&mut self, key: &K) -> Result<Option<V>, CanonError> {
+        match self {
+            BTreeMap::Entries(entries) => {
+                match entries.binary_search_by(|e| e.key.cmp(key)) {
+                    Ok(i) => Ok(Some(entries.remove(i).value)),
+                    Err(_) => Ok(None),
+                }
+            }
+            BTreeMap::Nodes(nodes) => {
+                let i = match child_index(nodes, key) {
+                    Some(i) => i,
+                    None => return Ok(None),
+                };
+
+                let (removed, len) = {
+                    let mut child = nodes[i].val_mut()?;
+                    let removed = child.remove_inner(key)?;
+                    (removed, child.node_len())
+                };
+
+                if len < MIN {
+                    Self::rebalance(nodes, i)?;
+                }
+                Ok(removed)
+            }
+        }
+    }
+
+    // Merges the underfull child at `i` with a sibling, splitting the merged
+    // node again if it holds too much
+    fn rebalance(
+        nodes: &mut Vec<Annotated<Self, A>>,
+        i: usize,
+    ) -> Result<(), CanonError> {
+        if nodes.len() < 2 {
+            return Ok(());
+        }
+
+        let (left, right) = if i + 1 < nodes.len() {
+            (i, i + 1)
+        } else {
+            (i - 1, i)
+        };
+
+        let mut right_node = nodes.remove(right);
+        let right_node = mem::take(&mut *right_node.val_mut()?);
+
+        let split = {
+            let mut left_node = nodes[left].val_mut()?;
+            left_node.merge(right_node);
+            left_node.split()
+        };
+
+        if let Some(split) = split {
+            nodes.insert(right, Annotated::new(split));
+        }
+        Ok(())
+    }
+}
+
+// The result of inserting into a subtree
+enum Insert<K, V, A> {
+    // The value replaced, if any
+    Replaced(Option<V>),
+    // The subtree overflowed, and was split into itself and the given node
+    Split(BTreeMap<K, V, A>),
+}
+
+// The index of the first child that could contain `key`
+fn child_index<C, A, K>(nodes: &[Annotated<C, A>], key: &K) -> Option<usize>
+where
+    C: Compound<A>,
+    A: Annotation<C::Leaf> + Borrow<MaxKey<K>>,
+    K: Ord,
+{
+    nodes
+        .iter()
+        .position(|node| match node.annotation().borrow() {
+            MaxKey::Maximum(max) => max >= key,
+            MaxKey::NegativeInfinity => false,
+        })
+}
@@ -0,0 +1,38 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+//
+// Copyright (c) DUSK NETWORK. All rights reserved.
+
+use canonical_derive::Canon;
+
+use crate::annotations::Keyed;
+
+/// A key-value pair, the leaf of the map collections
+#[derive(Clone, Canon, Debug, PartialEq)]
+pub struct Entry<K, V> {
+    pub(crate) key: K,
+    pub(crate) value: V,
+}
+
+impl<K, V> Entry<K, V> {
+    /// Returns the key of the entry
+    pub fn key(&self) -> &K {
+        &self.key
+    }
+
+    /// Returns the value of the entry
+    pub fn value(&self) -> &V {
+        &self.value
+    }
+
+    pub(crate) fn value_mut(&mut self) -> &mut V {
+        &mut self.value
+    }
+}
+
+impl<K, V> Keyed<K> for Entry<K, V> {
+    fn key(&self) -> &K {
+        &self.key
+    }
+}
@@ -0,0 +1,18 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+//
+// Copyright (c) DUSK NETWORK. All rights reserved.
+
+mod btree;
+mod entry;
+mod node;
+mod rope;
+mod trie;
+mod vector;
+
+pub use btree::BTreeMap;
+pub use entry::Entry;
+pub use rope::Rope;
+pub use trie::{KeyPrefix, Trie};
+pub use vector::Vector;
@@ -0,0 +1,102 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+//
+// Copyright (c) DUSK NETWORK. All rights reserved.
+
+use core::mem;
+
+use alloc::vec::Vec;
+
+use canonical::CanonError;
+
+use crate::annotations::{Annotated, Combine};
+use crate::compound::Compound;
+
+// The maximum number of leaves or children of a node
+pub(crate) const MAX: usize = 8;
+
+// The contents of a node of a balanced tree, either the leaves of a bottom
+// node, or the subtrees of equal height of an inner one
+pub(crate) enum Contents<L, N> {
+    Leaves(L),
+    Nodes(N),
+}
+
+// The contents of a `Balanced` node, borrowed or owned
+pub(crate) type ContentsOf<'a, B, A> =
+    Contents<&'a Vec<<B as Balanced<A>>::Item>, &'a Vec<Annotated<B, A>>>;
+pub(crate) type ContentsOfMut<'a, B, A> = Contents<
+    &'a mut Vec<<B as Balanced<A>>::Item>,
+    &'a mut Vec<Annotated<B, A>>,
+>;
+pub(crate) type OwnedContents<B, A> =
+    Contents<Vec<<B as Balanced<A>>::Item>, Vec<Annotated<B, A>>>;
+
+// A node of a balanced tree keeping its leaves and children in vectors, with
+// the operations shared by the collections built on one
+pub(crate) trait Balanced<A>: Compound<A> + Default {
+    // The items stored in the bottom nodes
+    type Item;
+
+    fn contents(&self) -> ContentsOf<'_, Self, A>;
+
+    fn contents_mut(&mut self) -> ContentsOfMut<'_, Self, A>;
+
+    fn into_contents(self) -> OwnedContents<Self, A>;
+
+    fn from_contents(contents: OwnedContents<Self, A>) -> Self;
+
+    // The number of leaves or children of the node
+    fn node_len(&self) -> usize {
+        match self.contents() {
+            Contents::Leaves(leaves) => leaves.len(),
+            Contents::Nodes(nodes) => nodes.len(),
+        }
+    }
+
+    // Moves the leaves or children of `other`, of the same height, to the end
+    // of the node
+    fn merge(&mut self, other: Self) {
+        match (self.contents_mut(), other.into_contents()) {
+            (Contents::Leaves(leaves), Contents::Leaves(mut other)) => {
+                leaves.append(&mut other)
+            }
+            (Contents::Nodes(nodes), Contents::Nodes(mut other)) => {
+                nodes.append(&mut other)
+            }
+            _ => unreachable!("Merged nodes are of equal height"),
+        }
+    }
+
+    // Splits off the upper half of an overfull node
+    fn split(&mut self) -> Option<Self> {
+        if self.node_len() <= MAX {
+            return None;
+        }
+        let at = self.node_len() / 2;
+        Some(Self::from_contents(match self.contents_mut() {
+            Contents::Leaves(leaves) => Contents::Leaves(leaves.split_off(at)),
+            Contents::Nodes(nodes) => Contents::Nodes(nodes.split_off(at)),
+        }))
+    }
+
+    // Removes roots with a single child, and empty ones
+    fn collapse(&mut self) -> Result<(), CanonError>
+    where
+        A: Combine<Self, A>,
+    {
+        while let Contents::Nodes(nodes) = self.contents_mut() {
+            match nodes.len() {
+                0 => *self = Self::default(),
+                1 => {
+                    let mut child = nodes.pop().expect("One child");
+                    let child = mem::take(&mut *child.val_mut()?);
+                    *self = child;
+                }
+                _ => break,
+            }
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,351 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+//
+// Copyright (c) DUSK NETWORK. All rights reserved.
+
+use core::borrow::Borrow;
+use core::mem;
+use core::ops::{Bound, RangeBounds};
+
+use alloc::vec::Vec;
+
+use canonical::{Canon, CanonError};
+use canonical_derive::Canon;
+
+use super::node::{
+    Balanced, Contents, ContentsOf, ContentsOfMut, OwnedContents,
+};
+use crate::annotations::{Annotated, Annotation, ByteLen, ByteOffset, Combine};
+use crate::branch::Branch;
+use crate::compound::{Child, ChildMut, Compound, MutableLeaves};
+use crate::reader::ByteReader;
+
+// The size of the chunks bytes are split into when added to a rope
+const CHUNK: usize = 256;
+
+/// A byte buffer, stored as a balanced tree of `Annotated` nodes over byte
+/// chunks.
+///
+/// Splitting, concatenating and slicing ropes take a number of steps
+/// logarithmic in their length, sharing all nodes left untouched between the
+/// parts and the original. The nodes cut along a split are merged into their
+/// untouched neighbours, so however often a rope is split, all nodes other
+/// than the root stay at least half full.
+#[derive(Clone, Canon, Debug)]
+pub enum Rope<A> {
+    /// Bottom node holding the chunks
+    Chunks(Vec<Vec<u8>>),
+    /// Inner node holding subtrees of equal height
+    Nodes(Vec<Annotated<Rope<A>, A>>),
+}
+
+impl<A> Default for Rope<A> {
+    fn default() -> Self {
+        Rope::Chunks(Vec::new())
+    }
+}
+
+impl<A> Compound<A> for Rope<A>
+where
+    A: Canon,
+{
+    type Leaf = Vec<u8>;
+
+    fn child(&self, ofs: usize) -> Child<'_, Self, A>
+    where
+        A: Annotation<Self::Leaf>,
+    {
+        match self {
+            Rope::Chunks(chunks) => match chunks.get(ofs) {
+                Some(chunk) => Child::Leaf(chunk),
+                None => Child::EndOfNode,
+            },
+            Rope::Nodes(nodes) => match nodes.get(ofs) {
+                Some(node) => Child::Node(node),
+                None => Child::EndOfNode,
+            },
+        }
+    }
+
+    fn child_mut(&mut self, ofs: usize) -> ChildMut<'_, Self, A>
+    where
+        A: Annotation<Self::Leaf>,
+    {
+        match self {
+            Rope::Chunks(chunks) => match chunks.get_mut(ofs) {
+                Some(chunk) => ChildMut::Leaf(chunk),
+                None => ChildMut::EndOfNode,
+            },
+            Rope::Nodes(nodes) => match nodes.get_mut(ofs) {
+                Some(node) => ChildMut::Node(node),
+                None => ChildMut::EndOfNode,
+            },
+        }
+    }
+}
+
+impl<A> Balanced<A> for Rope<A>
+where
+    A: Canon,
+{
+    type Item = Vec<u8>;
+
+    fn contents(&self) -> ContentsOf<'_, Self, A> {
+        match self {
+            Rope::Chunks(chunks) => Contents::Leaves(chunks),
+            Rope::Nodes(nodes) => Contents::Nodes(nodes),
+        }
+    }
+
+    fn contents_mut(&mut self) -> ContentsOfMut<'_, Self, A> {
+        match self {
+            Rope::Chunks(chunks) => Contents::Leaves(chunks),
+            Rope::Nodes(nodes) => Contents::Nodes(nodes),
+        }
+    }
+
+    fn into_contents(self) -> OwnedContents<Self, A> {
+        match self {
+            Rope::Chunks(chunks) => Contents::Leaves(chunks),
+            Rope::Nodes(nodes) => Contents::Nodes(nodes),
+        }
+    }
+
+    fn from_contents(contents: OwnedContents<Self, A>) -> Self {
+        match contents {
+            Contents::Leaves(chunks) => Rope::Chunks(chunks),
+            Contents::Nodes(nodes) => Rope::Nodes(nodes),
+        }
+    }
+}
+
+impl<A> MutableLeaves for Rope<A> {}
+
+impl<A> Rope<A>
+where
+    A: Canon + Combine<Self, A> + Borrow<ByteLen>,
+{
+    /// Creates a new empty rope
+    pub fn new() -> Self {
+        Default::default()
+    }
+
+    /// Creates a rope holding `bytes`
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, CanonError> {
+        let mut rope = Self::new();
+        rope.extend_from_slice(bytes)?;
+        Ok(rope)
+    }
+
+    /// Returns the number of bytes in the rope
+    pub fn len(&self) -> u64 {
+        ByteLen::combine(self).into()
+    }
+
+    /// Returns true if the rope holds no bytes
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Appends `bytes` to the end of the rope
+    pub fn extend_from_slice(
+        &mut self,
+        bytes: &[u8],
+    ) -> Result<(), CanonError> {
+        for chunk in bytes.chunks(CHUNK) {
+            self.append(Rope::Chunks(vec![chunk.to_vec()]))?;
+        }
+        Ok(())
+    }
+
+    /// Moves the bytes of `other` to the end of the rope
+    pub fn append(&mut self, other: Self) -> Result<(), CanonError> {
+        if other.is_empty() {
+            return Ok(());
+        }
+        if self.is_empty() {
+            *self = other;
+            return Ok(());
+        }
+
+        let height = self.height()?;
+        let other_height = other.height()?;
+
+        let split = if height >= other_height {
+            self.append_lower(other, height, other_height)?
+        } else {
+            let left = mem::replace(self, other);
+            self.prepend_lower(left, other_height, height)?
+        };
+
+        if let Some(right) = split {
+            // grow a new root above the split one
+            let left = mem::take(self);
+            *self =
+                Rope::Nodes(vec![Annotated::new(left), Annotated::new(right)]);
+        }
+        Ok(())
+    }
+
+    /// Splits the rope in two at byte `at`, keeping the bytes before it and
+    /// returning the rest
+    pub fn split_off(&mut self, at: u64) -> Result<Self, CanonError> {
+        let (left, right) = mem::take(self).split_at(at)?;
+        *self = left;
+        Ok(right)
+    }
+
+    /// Returns a rope holding the bytes in `range`, if it is within the
+    /// bounds of the rope
+    pub fn slice<R>(&self, range: R) -> Result<Option<Self>, CanonError>
+    where
+        R: RangeBounds<u64>,
+    {
+        let len = self.len();
+        let start = match range.start_bound() {
+            Bound::Included(start) => Some(*start),
+            Bound::Excluded(start) => start.checked_add(1),
+            Bound::Unbounded => Some(0),
+        };
+        let end = match range.end_bound() {
+            Bound::Included(end) => end.checked_add(1),
+            Bound::Excluded(end) => Some(*end),
+            Bound::Unbounded => Some(len),
+        };
+        let (start, end) = match (start, end) {
+            (Some(start), Some(end)) if start <= end && end <= len => {
+                (start, end)
+            }
+            _ => return Ok(None),
+        };
+
+        let mut slice = self.clone().split_off(start)?;
+        slice.split_off(end - start)?;
+        Ok(Some(slice))
+    }
+
+    /// Returns a reader streaming the bytes of the rope from byte `at`, if it
+    /// is within the rope
+    pub fn reader(
+        &self,
+        at: u64,
+    ) -> Result<Option<ByteReader<'_, Self, A>>, CanonError> {
+        let mut walker = ByteOffset::new(at);
+        Ok(Branch::walk(self, &mut walker)?
+            .map(|branch| branch.into_reader(walker.remaining() as usize)))
+    }
+
+    // The height of the tree, all chunks being at the same depth
+    fn height(&self) -> Result<usize, CanonError> {
+        match self {
+            Rope::Chunks(_) => Ok(0),
+            Rope::Nodes(nodes) => Ok(nodes[0].val()?.height()? + 1),
+        }
+    }
+
+    // Appends `other`, of at most the same height, along the right edge of
+    // the tree, returning a split-off node if the top one overflowed
+    fn append_lower(
+        &mut self,
+        other: Self,
+        height: usize,
+        other_height: usize,
+    ) -> Result<Option<Self>, CanonError> {
+        if height == other_height {
+            self.merge(other);
+        } else if let Rope::Nodes(nodes) = self {
+            let last = nodes.last_mut().expect("Nodes have children");
+            let split = last.val_mut()?.append_lower(
+                other,
+                height - 1,
+                other_height,
+            )?;
+            if let Some(split) = split {
+                nodes.push(Annotated::new(split));
+            }
+        }
+        Ok(self.split())
+    }
+
+    // Prepends `other`, of at most the same height, along the left edge of
+    // the tree, returning a split-off node if the top one overflowed
+    fn prepend_lower(
+        &mut self,
+        other: Self,
+        height: usize,
+        other_height: usize,
+    ) -> Result<Option<Self>, CanonError> {
+        if height == other_height {
+            let right = mem::replace(self, other);
+            self.merge(right);
+        } else if let Rope::Nodes(nodes) = self {
+            let split = nodes[0].val_mut()?.prepend_lower(
+                other,
+                height - 1,
+                other_height,
+            )?;
+            if let Some(split) = split {
+                nodes.insert(1, Annotated::new(split));
+            }
+        }
+        Ok(self.split())
+    }
+
+    fn split_at(self, at: u64) -> Result<(Self, Self), CanonError> {
+        match self {
+            Rope::Chunks(mut chunks) => {
+                let mut at = at;
+                let mut i = 0;
+                while i < chunks.len() && at >= chunks[i].len() as u64 {
+                    at -= chunks[i].len() as u64;
+                    i += 1;
+                }
+
+                let mut right = chunks.split_off(i);
+                match right.first_mut() {
+                    // the split falls within the first chunk on the right
+                    Some(first) if at > 0 => {
+                        let tail = first.split_off(at as usize);
+                        chunks.push(mem::replace(first, tail));
+                    }
+                    _ => (),
+                }
+                Ok((Rope::Chunks(chunks), Rope::Chunks(right)))
+            }
+            Rope::Nodes(mut nodes) => {
+                let mut at = at;
+                let mut i = 0;
+                while i < nodes.len() {
+                    let len: u64 = nodes[i].annotation().borrow().into();
+                    if at < len {
+                        break;
+                    }
+                    at -= len;
+                    i += 1;
+                }
+
+                let mut right = nodes.split_off(i);
+                let mut left = Rope::Nodes(nodes);
+                left.collapse()?;
+                if right.is_empty() {
+                    return Ok((left, Self::new()));
+                }
+
+                let mut child = right.remove(0);
+                let child = mem::take(&mut *child.val_mut()?);
+                let (child_left, child_right) = child.split_at(at)?;
+
+                // merging the cut child into untouched nodes keeps them filled
+                left.append(child_left)?;
+
+                let mut right = Rope::Nodes(right);
+                right.collapse()?;
+                let mut right_rest = child_right;
+                right_rest.append(right)?;
+
+                Ok((left, right_rest))
+            }
+        }
+    }
+}
@@ -0,0 +1,301 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+//
+// Copyright (c) DUSK NETWORK. All rights reserved.
+
+use core::mem;
+
+use canonical::{Canon, CanonError};
+use canonical_derive::Canon;
+
+use super::Entry;
+use crate::annotations::{Annotated, Annotation, Combine};
+use crate::branch::{Branch, BranchIterator, MappedBranch};
+use crate::branch_mut::{BranchMut, BranchMutMappedMut};
+use crate::compound::{Child, ChildMut, Compound, IterChild};
+use crate::walk::{Step, Walk, Walker};
+
+// The number of children of a node, one for every value of a nibble
+const WIDTH: usize = 16;
+
+// The nibble of `key` at `depth`, most significant first
+fn nibble(key: &[u8], depth: usize) -> Option<usize> {
+    let byte = *key.get(depth / 2)? as usize;
+    Some(match depth % 2 {
+        0 => byte >> 4,
+        _ => byte & 0xf,
+    })
+}
+
+#[derive(Clone, Canon, Debug, Default)]
+enum Slot<K, V, A> {
+    #[default]
+    Empty,
+    Leaf(Entry<K, V>),
+    Node(Annotated<Trie<K, V, A>, A>),
+}
+
+/// A map keyed by byte arrays, stored as a radix trie of `Annotated` nodes.
+///
+/// Every node has a child slot for each of the 16 values of a nibble of the
+/// key, so the offsets of the path of a `Branch` to an entry are the leading
+/// nibbles of its key. Entries are stored at the shallowest depth at which
+/// their key differs from all others, leaving the shape of the trie, and
+/// thereby its annotations, independent of the order of insertions and
+/// removals.
+///
+/// The keys are deliberately narrowed from byte strings to arrays of a fixed,
+/// non-zero length, such as hashes, so that no key is a prefix of another, and
+/// two keys always differ at some nibble. Using empty arrays as keys fails to
+/// compile.
+#[derive(Clone, Canon, Debug)]
+pub struct Trie<K, V, A>([Slot<K, V, A>; WIDTH]);
+
+impl<K, V, A> Default for Trie<K, V, A> {
+    fn default() -> Self {
+        Trie(Default::default())
+    }
+}
+
+impl<K, V, A> Compound<A> for Trie<K, V, A>
+where
+    K: Canon,
+    V: Canon,
+    A: Canon,
+{
+    type Leaf = Entry<K, V>;
+
+    fn child(&self, ofs: usize) -> Child<'_, Self, A>
+    where
+        A: Annotation<Self::Leaf>,
+    {
+        match self.0.get(ofs) {
+            Some(Slot::Empty) => Child::Empty,
+            Some(Slot::Leaf(entry)) => Child::Leaf(entry),
+            Some(Slot::Node(node)) => Child::Node(node),
+            None => Child::EndOfNode,
+        }
+    }
+
+    fn child_mut(&mut self, ofs: usize) -> ChildMut<'_, Self, A>
+    where
+        A: Annotation<Self::Leaf>,
+    {
+        match self.0.get_mut(ofs) {
+            Some(Slot::Empty) => ChildMut::Empty,
+            Some(Slot::Leaf(entry)) => ChildMut::Leaf(entry),
+            Some(Slot::Node(node)) => ChildMut::Node(node),
+            None => ChildMut::EndOfNode,
+        }
+    }
+}
+
+impl<const N: usize, V, A> Trie<[u8; N], V, A>
+where
+    V: Canon,
+    A: Canon + Combine<Self, A>,
+{
+    // Evaluated where keys are used, to reject empty ones at compile time
+    const NON_EMPTY_KEYS: () = assert!(N > 0, "Trie keys must not be empty");
+
+    /// Creates a new empty trie
+    pub fn new() -> Self {
+        let () = Self::NON_EMPTY_KEYS;
+        Default::default()
+    }
+
+    /// Returns true if the trie holds no entries
+    pub fn is_empty(&self) -> bool {
+        self.0.iter().all(|slot| matches!(slot, Slot::Empty))
+    }
+
+    /// Returns a branch to the value stored under `key`, if any
+    pub fn get(
+        &self,
+        key: &[u8; N],
+    ) -> Result<Option<MappedBranch<'_, Self, A, V>>, CanonError> {
+        let key = key.as_ref();
+        Ok(Branch::walk(self, KeyPrefix::new(key))?
+            .filter(|branch| branch.key.as_ref() == key)
+            .map(|branch| branch.map_leaf(Entry::value)))
+    }
+
+    /// Returns a mutable branch to the value stored under `key`, if any
+    ///
+    /// The annotations are recombined when the branch is dropped.
+    pub fn get_mut(
+        &mut self,
+        key: &[u8; N],
+    ) -> Result<Option<BranchMutMappedMut<'_, Self, A, V>>, CanonError> {
+        let key = key.as_ref();
+        Ok(BranchMut::walk(self, KeyPrefix::new(key))?
+            .filter(|branch| branch.key.as_ref() == key)
+            .map(|branch| branch.map_leaf_mut(Entry::value_mut)))
+    }
+
+    /// Returns an iterator over the entries with keys starting with
+    /// `prefix`, in the order of their keys
+    pub fn prefix<'p>(
+        &self,
+        prefix: &'p [u8],
+    ) -> BranchIterator<'_, Self, A, KeyPrefix<'p>> {
+        BranchIterator::new(self, KeyPrefix::new(prefix))
+    }
+
+    /// Inserts `value` under `key`, returning the value previously stored
+    /// under it, if any
+    pub fn insert(
+        &mut self,
+        key: [u8; N],
+        value: V,
+    ) -> Result<Option<V>, CanonError> {
+        let () = Self::NON_EMPTY_KEYS;
+        self.insert_at(Entry { key, value }, 0)
+    }
+
+    fn insert_at(
+        &mut self,
+        entry: Entry<[u8; N], V>,
+        depth: usize,
+    ) -> Result<Option<V>, CanonError> {
+        let i = nibble(&entry.key, depth)
+            .expect("Keys of equal length differ within it");
+
+        match &mut self.0[i] {
+            Slot::Empty => self.0[i] = Slot::Leaf(entry),
+            Slot::Leaf(leaf) if leaf.key == entry.key => {
+                return Ok(Some(mem::replace(&mut leaf.value, entry.value)));
+            }
+            Slot::Leaf(_) => {
+                // push the present entry down, to where the keys differ
+                if let Slot::Leaf(leaf) = mem::take(&mut self.0[i]) {
+                    let node = Self::pair(leaf, entry, depth + 1);
+                    self.0[i] = Slot::Node(Annotated::new(node));
+                }
+            }
+            Slot::Node(node) => {
+                return node.val_mut()?.insert_at(entry, depth + 1)
+            }
+        }
+        Ok(None)
+    }
+
+    // Creates a node at `depth` holding two entries with different keys
+    fn pair(a: Entry<[u8; N], V>, b: Entry<[u8; N], V>, depth: usize) -> Self {
+        let i = nibble(&a.key, depth)
+            .expect("Keys of equal length differ within it");
+        let j = nibble(&b.key, depth)
+            .expect("Keys of equal length differ within it");
+
+        let mut node = Self::default();
+        if i == j {
+            let below = Self::pair(a, b, depth + 1);
+            node.0[i] = Slot::Node(Annotated::new(below));
+        } else {
+            node.0[i] = Slot::Leaf(a);
+            node.0[j] = Slot::Leaf(b);
+        }
+        node
+    }
+
+    /// Removes the entry with `key` from the trie, returning its value, if
+    /// any
+    pub fn remove(&mut self, key: &[u8; N]) -> Result<Option<V>, CanonError> {
+        self.remove_at(key, 0)
+    }
+
+    fn remove_at(
+        &mut self,
+        key: &[u8],
+        depth: usize,
+    ) -> Result<Option<V>, CanonError> {
+        let i = match nibble(key, depth) {
+            Some(i) => i,
+            None => return Ok(None),
+        };
+
+        match &mut self.0[i] {
+            Slot::Empty => Ok(None),
+            Slot::Leaf(leaf) => {
+                if leaf.key.as_ref() != key {
+                    return Ok(None);
+                }
+                match mem::take(&mut self.0[i]) {
+                    Slot::Leaf(leaf) => Ok(Some(leaf.value)),
+                    _ => unreachable!(),
+                }
+            }
+            Slot::Node(node) => {
+                let (removed, last) = {
+                    let mut node = node.val_mut()?;
+                    let removed = node.remove_at(key, depth + 1)?;
+                    (removed, node.take_last_leaf())
+                };
+                // move an entry left alone in a node up to its parent
+                if let Some(leaf) = last {
+                    self.0[i] = Slot::Leaf(leaf);
+                }
+                Ok(removed)
+            }
+        }
+    }
+
+    // Takes the entry out of a node holding no other children
+    fn take_last_leaf(&mut self) -> Option<Entry<[u8; N], V>> {
+        let mut occupied = self
+            .0
+            .iter()
+            .enumerate()
+            .filter(|(_, slot)| !matches!(slot, Slot::Empty));
+
+        match (occupied.next(), occupied.next()) {
+            (Some((i, Slot::Leaf(_))), None) => match mem::take(&mut self.0[i])
+            {
+                Slot::Leaf(leaf) => Some(leaf),
+                _ => unreachable!(),
+            },
+            _ => None,
+        }
+    }
+}
+
+/// Walker visiting the entries of a `Trie` with keys starting with a prefix
+///
+/// Only the child at the nibble of the prefix is visited in nodes at depths
+/// within the prefix, so the walk follows the prefix directly.
+pub struct KeyPrefix<'p>(&'p [u8]);
+
+impl<'p> KeyPrefix<'p> {
+    /// Creates a walker visiting the entries with keys starting with
+    /// `prefix`
+    pub fn new(prefix: &'p [u8]) -> Self {
+        KeyPrefix(prefix)
+    }
+}
+
+impl<'p, K, V, A> Walker<Trie<K, V, A>, A> for KeyPrefix<'p>
+where
+    K: Canon + AsRef<[u8]>,
+    V: Canon,
+    A: Canon + Combine<Trie<K, V, A>, A>,
+{
+    fn walk(&mut self, walk: Walk<Trie<K, V, A>, A>) -> Step {
+        let along = nibble(self.0, walk.depth());
+
+        for (i, child) in walk.children() {
+            if along.is_some() && walk.node_offset(i) != along {
+                continue;
+            }
+            match child {
+                IterChild::Leaf(leaf) => {
+                    if leaf.key.as_ref().starts_with(self.0) {
+                        return Step::Found(i);
+                    }
+                }
+                IterChild::Node(_) => return Step::Into(i),
+            }
+        }
+        Step::Advance
+    }
+}
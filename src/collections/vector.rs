@@ -0,0 +1,225 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+//
+// Copyright (c) DUSK NETWORK. All rights reserved.
+
+use core::borrow::Borrow;
+use core::mem;
+
+use canonical::{Canon, CanonError};
+use canonical_derive::Canon;
+
+use crate::annotations::{Annotated, Annotation, Cardinality, Combine};
+use crate::compound::{Child, ChildMut, Compound, MutableLeaves};
+
+// The number of children of every node
+const N: usize = 4;
+
+/// A persistent vector, stored as a balanced tree of fixed-arity nodes.
+///
+/// Elements are pushed and popped at the end, and found by index with `Nth`
+/// when the annotation can be borrowed as `Cardinality`. Clones share their
+/// nodes until one of them is changed.
+#[derive(Clone, Canon, Debug)]
+#[allow(clippy::large_enum_variant)]
+pub enum Vector<T, A> {
+    /// Bottom node holding the elements
+    Leaves([Option<T>; N]),
+    /// Inner node holding subtrees of equal height
+    Nodes([Option<Annotated<Vector<T, A>, A>>; N]),
+}
+
+impl<T, A> Default for Vector<T, A> {
+    fn default() -> Self {
+        Vector::Leaves(Default::default())
+    }
+}
+
+impl<T, A> Compound<A> for Vector<T, A>
+where
+    T: Canon,
+    A: Canon,
+{
+    type Leaf = T;
+
+    fn child(&self, ofs: usize) -> Child<'_, Self, A>
+    where
+        A: Annotation<Self::Leaf>,
+    {
+        match self {
+            Vector::Leaves(leaves) => match leaves.get(ofs) {
+                Some(Some(leaf)) => Child::Leaf(leaf),
+                Some(None) => Child::Empty,
+                None => Child::EndOfNode,
+            },
+            Vector::Nodes(nodes) => match nodes.get(ofs) {
+                Some(Some(node)) => Child::Node(node),
+                Some(None) => Child::Empty,
+                None => Child::EndOfNode,
+            },
+        }
+    }
+
+    fn child_mut(&mut self, ofs: usize) -> ChildMut<'_, Self, A>
+    where
+        A: Annotation<Self::Leaf>,
+    {
+        match self {
+            Vector::Leaves(leaves) => match leaves.get_mut(ofs) {
+                Some(Some(leaf)) => ChildMut::Leaf(leaf),
+                Some(None) => ChildMut::Empty,
+                None => ChildMut::EndOfNode,
+            },
+            Vector::Nodes(nodes) => match nodes.get_mut(ofs) {
+                Some(Some(node)) => ChildMut::Node(node),
+                Some(None) => ChildMut::Empty,
+                None => ChildMut::EndOfNode,
+            },
+        }
+    }
+}
+
+impl<T, A> MutableLeaves for Vector<T, A> {}
+
+// The result of pushing into a subtree
+enum Push<T> {
+    Ok,
+    // The subtree of the given height is full
+    Full(T, usize),
+}
+
+impl<T, A> Vector<T, A>
+where
+    T: Canon,
+    A: Canon + Combine<Self, A>,
+{
+    /// Creates a new empty vector
+    pub fn new() -> Self {
+        Default::default()
+    }
+
+    /// Returns the number of elements in the vector
+    pub fn len(&self) -> u64
+    where
+        A: Borrow<Cardinality>,
+    {
+        Cardinality::combine(self).into()
+    }
+
+    /// Returns true if the vector holds no elements
+    pub fn is_empty(&self) -> bool {
+        match self {
+            Vector::Leaves(leaves) => leaves[0].is_none(),
+            Vector::Nodes(nodes) => nodes[0].is_none(),
+        }
+    }
+
+    /// Appends an element to the back of the vector
+    pub fn push(&mut self, t: T) -> Result<(), CanonError> {
+        if let Push::Full(t, height) = self.push_inner(t)? {
+            // grow a new root above the full tree
+            let old = mem::take(self);
+            let mut nodes: [Option<Annotated<Self, A>>; N] = Default::default();
+            nodes[0] = Some(Annotated::new(old));
+            nodes[1] = Some(Annotated::new(Self::spine(t, height)));
+            *self = Vector::Nodes(nodes);
+        }
+        Ok(())
+    }
+
+    fn push_inner(&mut self, t: T) -> Result<Push<T>, CanonError> {
+        match self {
+            Vector::Leaves(leaves) => {
+                match leaves.iter_mut().find(|leaf| leaf.is_none()) {
+                    Some(slot) => {
+                        *slot = Some(t);
+                        Ok(Push::Ok)
+                    }
+                    None => Ok(Push::Full(t, 0)),
+                }
+            }
+            Vector::Nodes(nodes) => {
+                let last = last_index(nodes);
+                let pushed = nodes[last]
+                    .as_mut()
+                    .expect("Nodes are never empty")
+                    .val_mut()?
+                    .push_inner(t)?;
+
+                match pushed {
+                    Push::Full(t, height) if last + 1 < N => {
+                        nodes[last + 1] =
+                            Some(Annotated::new(Self::spine(t, height)));
+                        Ok(Push::Ok)
+                    }
+                    Push::Full(t, height) => Ok(Push::Full(t, height + 1)),
+                    Push::Ok => Ok(Push::Ok),
+                }
+            }
+        }
+    }
+
+    // A subtree of `height` holding a single element
+    fn spine(t: T, height: usize) -> Self {
+        let mut leaves: [Option<T>; N] = Default::default();
+        leaves[0] = Some(t);
+        let mut node = Vector::Leaves(leaves);
+
+        for _ in 0..height {
+            let mut nodes: [Option<Annotated<Self, A>>; N] = Default::default();
+            nodes[0] = Some(Annotated::new(node));
+            node = Vector::Nodes(nodes);
+        }
+        node
+    }
+
+    /// Removes the last element of the vector, if any
+    pub fn pop(&mut self) -> Result<Option<T>, CanonError> {
+        let popped = self.pop_inner()?;
+
+        // collapse roots with a single child
+        loop {
+            match self {
+                Vector::Nodes(nodes) if nodes[1].is_none() => {
+                    *self = match nodes[0].take() {
+                        Some(child) => child.val()?.clone(),
+                        None => Self::default(),
+                    }
+                }
+                _ => return Ok(popped),
+            }
+        }
+    }
+
+    fn pop_inner(&mut self) -> Result<Option<T>, CanonError> {
+        match self {
+            Vector::Leaves(leaves) => {
+                Ok(leaves.iter_mut().rev().find_map(|leaf| leaf.take()))
+            }
+            Vector::Nodes(nodes) => {
+                let last = last_index(nodes);
+                let slot = &mut nodes[last];
+
+                let (popped, empty) = {
+                    let mut child = slot
+                        .as_mut()
+                        .expect("Nodes are never empty")
+                        .val_mut()?;
+                    let popped = child.pop_inner()?;
+                    (popped, child.is_empty())
+                };
+
+                if empty {
+                    *slot = None;
+                }
+                Ok(popped)
+            }
+        }
+    }
+}
+
+// The index of the last occupied slot of a node, or 0 if none
+fn last_index<T>(slots: &[Option<T>]) -> usize {
+    slots.iter().rposition(Option::is_some).unwrap_or(0)
+}
@@ -0,0 +1,178 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+//
+// Copyright (c) DUSK NETWORK. All rights reserved.
+
+use crate::annotations::Combine;
+use crate::compound::{Child, Compound};
+use crate::walk::{Step, Walk, Walker};
+
+/// Walks with `walker`, resuming the walk after every leaf found that is not
+/// accepted
+fn walk_accepting<C, A, W, F>(
+    walker: &mut W,
+    walk: Walk<C, A>,
+    mut accept: F,
+) -> Step
+where
+    C: Compound<A>,
+    A: Combine<C, A>,
+    W: Walker<C, A>,
+    F: FnMut(&C::Leaf) -> bool,
+{
+    let mut base = 0;
+    while let Some(rest) = walk.skip_children(base) {
+        match walker.walk(rest) {
+            Step::Found(i) => match walk.child(base + i) {
+                Child::Leaf(l) if !accept(l) => base += i + 1,
+                _ => return Step::Found(base + i),
+            },
+            Step::Into(i) => return Step::Into(base + i),
+            step => return step,
+        }
+    }
+    Step::Advance
+}
+
+/// Walker that only finds the leaves of another walker matching a predicate
+pub struct FilterLeaves<W, F> {
+    walker: W,
+    predicate: F,
+}
+
+impl<C, A, W, F> Walker<C, A> for FilterLeaves<W, F>
+where
+    C: Compound<A>,
+    A: Combine<C, A>,
+    W: Walker<C, A>,
+    F: FnMut(&C::Leaf) -> bool,
+{
+    fn walk(&mut self, walk: Walk<C, A>) -> Step {
+        walk_accepting(&mut self.walker, walk, &mut self.predicate)
+    }
+}
+
+/// Walker that finds at most `n` leaves of another walker
+pub struct TakeLeaves<W> {
+    walker: W,
+    remaining: usize,
+}
+
+impl<C, A, W> Walker<C, A> for TakeLeaves<W>
+where
+    C: Compound<A>,
+    A: Combine<C, A>,
+    W: Walker<C, A>,
+{
+    fn walk(&mut self, walk: Walk<C, A>) -> Step {
+        if self.remaining == 0 {
+            return Step::Abort;
+        }
+        let step = self.walker.walk(walk);
+        if let Step::Found(_) = step {
+            self.remaining -= 1;
+        }
+        step
+    }
+}
+
+/// Walker that passes over the first `n` leaves found by another walker
+pub struct SkipLeaves<W> {
+    walker: W,
+    remaining: usize,
+}
+
+impl<C, A, W> Walker<C, A> for SkipLeaves<W>
+where
+    C: Compound<A>,
+    A: Combine<C, A>,
+    W: Walker<C, A>,
+{
+    fn walk(&mut self, walk: Walk<C, A>) -> Step {
+        let remaining = &mut self.remaining;
+        walk_accepting(&mut self.walker, walk, |_| {
+            if *remaining > 0 {
+                *remaining -= 1;
+                false
+            } else {
+                true
+            }
+        })
+    }
+}
+
+/// Walker that continues with a second walker once the first one is done
+///
+/// The first walker is done when it aborts, or when it advances past the
+/// root, having no more leaves to find. The second walker then picks up the
+/// walk from where the first one stopped, not from the start of the
+/// collection, so it never runs if the first walker went through all of it,
+/// unlike the second iterator of `Iterator::chain`.
+pub struct ThenLeaves<W, V> {
+    first: W,
+    second: V,
+    first_done: bool,
+}
+
+impl<C, A, W, V> Walker<C, A> for ThenLeaves<W, V>
+where
+    C: Compound<A>,
+    A: Combine<C, A>,
+    W: Walker<C, A>,
+    V: Walker<C, A>,
+{
+    fn walk(&mut self, walk: Walk<C, A>) -> Step {
+        if !self.first_done {
+            match self.first.walk(walk.clone()) {
+                Step::Abort => self.first_done = true,
+                Step::Advance if walk.depth() == 0 => self.first_done = true,
+                step => return step,
+            }
+        }
+        self.second.walk(walk)
+    }
+}
+
+/// Combinators for walkers
+///
+/// Since walkers are usually run through an iterator, where the state of the
+/// walker is kept between leaves, the counts of `take_leaves` and
+/// `skip_leaves` are over the whole iteration.
+pub trait WalkerExt: Sized {
+    /// Only find the leaves matching `predicate`
+    fn filter_leaves<F>(self, predicate: F) -> FilterLeaves<Self, F> {
+        FilterLeaves {
+            walker: self,
+            predicate,
+        }
+    }
+
+    /// Find at most `n` leaves, aborting afterwards
+    fn take_leaves(self, n: usize) -> TakeLeaves<Self> {
+        TakeLeaves {
+            walker: self,
+            remaining: n,
+        }
+    }
+
+    /// Pass over the first `n` leaves found
+    fn skip_leaves(self, n: usize) -> SkipLeaves<Self> {
+        SkipLeaves {
+            walker: self,
+            remaining: n,
+        }
+    }
+
+    /// Continue the walk with `other` once this walker aborts or runs out of
+    /// leaves, from where this walker stopped
+    fn then_leaves<V>(self, other: V) -> ThenLeaves<Self, V> {
+        ThenLeaves {
+            first: self,
+            second: other,
+            first_done: false,
+        }
+    }
+}
+
+impl<W> WalkerExt for W {}
@@ -7,7 +7,7 @@
 use core::marker::PhantomData;
 
 use crate::annotations::{Annotated, Annotation, WrappedAnnotation};
-use canonical::Canon;
+use canonical::{Canon, CanonError};
 
 /// The response of the `child` method on a `Compound` node.
 pub enum Child<'a, C, A>
@@ -39,23 +39,36 @@ where
     EndOfNode,
 }
 
+/// The contents of a child slot, removed from a `Compound` node.
+pub enum OwnedChild<C, A>
+where
+    C: Compound<A>,
+{
+    /// Child was a leaf
+    Leaf(C::Leaf),
+    /// Child was an annotated subtree node
+    Node(Annotated<C, A>),
+    /// Slot was empty
+    Empty,
+}
+
 /// A type that can recursively contain itself and leaves.
 pub trait Compound<A>: Sized + Canon {
     /// The leaf type of the Compound collection
     type Leaf;
 
     /// Returns a reference to a possible child at specified offset
-    fn child(&self, ofs: usize) -> Child<Self, A>
+    fn child(&self, ofs: usize) -> Child<'_, Self, A>
     where
         A: Annotation<Self::Leaf>;
 
     /// Returns a mutable reference to a possible child at specified offset
-    fn child_mut(&mut self, ofs: usize) -> ChildMut<Self, A>
+    fn child_mut(&mut self, ofs: usize) -> ChildMut<'_, Self, A>
     where
         A: Annotation<Self::Leaf>;
 
     /// Returns an iterator over the children of the Compound node.
-    fn children(&self) -> ChildIterator<Self, A> {
+    fn children(&self) -> ChildIterator<'_, Self, A> {
         ChildIterator {
             node: self,
             ofs: 0,
@@ -64,6 +77,33 @@ pub trait Compound<A>: Sized + Canon {
     }
 }
 
+/// A `Compound` that supports inserting leaves into its nodes
+pub trait CompoundInsert<A>: Compound<A> {
+    /// Inserts `leaf` in front of the child at `ofs`, such that the new leaf
+    /// is found at `ofs`
+    fn insert_leaf(
+        &mut self,
+        ofs: usize,
+        leaf: Self::Leaf,
+    ) -> Result<(), CanonError>
+    where
+        A: Annotation<Self::Leaf>;
+}
+
+/// A `Compound` that supports removing children from its nodes
+pub trait CompoundRemove<A>: Compound<A> {
+    /// Removes the child at `ofs`, returning the previous contents of the slot
+    ///
+    /// Implementations are free to leave the slot empty, or to move the
+    /// following children back.
+    fn remove_child(
+        &mut self,
+        ofs: usize,
+    ) -> Result<OwnedChild<Self, A>, CanonError>
+    where
+        A: Annotation<Self::Leaf>;
+}
+
 /// The kinds of children you can encounter iterating over a Compound
 pub enum IterChild<'a, C, A>
 where
@@ -81,7 +121,7 @@ where
     C: Compound<A>,
 {
     /// Returns the annotation of the child
-    pub fn annotation(&self) -> WrappedAnnotation<A> {
+    pub fn annotation(&self) -> WrappedAnnotation<'_, A> {
         match self {
             IterChild::Leaf(l) => WrappedAnnotation::Owned(A::from_leaf(l)),
             IterChild::Node(a) => WrappedAnnotation::Borrowed(a.annotation()),
@@ -131,3 +171,14 @@ where
 /// Note that this is still safe to implement, since it can only cause logical
 /// errors, not undefined behaviour,
 pub trait MutableLeaves {}
+
+/// Marker trait to signal that the leaves of a datastructure are ordered by
+/// their keys.
+///
+/// Leaves must appear in ascending key order when visiting the children of
+/// every node by increasing offset, which allows searching for a key by
+/// descending into the first subtree whose `MaxKey` is not smaller than it.
+///
+/// As with `MutableLeaves`, implementing this for an unordered collection can
+/// only cause logical errors, such as lookups failing to find present keys.
+pub trait SortedLeaves {}
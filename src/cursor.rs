@@ -0,0 +1,261 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+//
+// Copyright (c) DUSK NETWORK. All rights reserved.
+
+use core::borrow::Borrow;
+
+use alloc::vec::Vec;
+
+use canonical::CanonError;
+
+use crate::annotations::{leaves_before, Cardinality, Combine, Offset};
+use crate::branch::{Branch, Level, PartialBranch};
+use crate::branch_mut::{BranchMut, LevelMut, PartialBranchMut};
+use crate::compound::{Child, Compound};
+use crate::walk::{AllLeaves, Direction};
+
+/// A branch that can be moved between the leaves of a collection in place.
+///
+/// Moving the cursor only loads the nodes that are not shared between the
+/// old and the new branch. If a move fails, either finding no leaf or with an
+/// error loading a node, the cursor stays where it was, so it always points at
+/// a leaf.
+#[derive(Debug)]
+pub struct Cursor<'a, C, A> {
+    branch: PartialBranch<'a, C, A>,
+    // The position to move back to if a move fails, kept between moves to
+    // reuse its allocation
+    saved: Vec<usize>,
+}
+
+impl<'a, C, A> Branch<'a, C, A>
+where
+    C: Compound<A>,
+    A: Combine<C, A>,
+{
+    /// Turns the branch into a cursor pointing at the same leaf
+    pub fn into_cursor(self) -> Cursor<'a, C, A> {
+        Cursor {
+            branch: self.0,
+            saved: Vec::new(),
+        }
+    }
+}
+
+impl<'a, C, A> Cursor<'a, C, A>
+where
+    C: Compound<A>,
+    A: Combine<C, A>,
+{
+    /// Returns the leaf pointed at by the cursor
+    pub fn current(&self) -> &C::Leaf {
+        self.branch.leaf().expect("Invalid cursor")
+    }
+
+    /// Returns a slice into the levels of the tree.
+    pub fn levels(&self) -> &[Level<'_, C, A>] {
+        self.branch.levels()
+    }
+
+    /// Moves the cursor to the next leaf, if any
+    #[allow(clippy::should_implement_trait)]
+    pub fn next(&mut self) -> Result<Option<&C::Leaf>, CanonError> {
+        self.step(Direction::Forward)
+    }
+
+    /// Moves the cursor to the previous leaf, if any
+    pub fn prev(&mut self) -> Result<Option<&C::Leaf>, CanonError> {
+        self.step(Direction::Backward)
+    }
+
+    fn step(
+        &mut self,
+        direction: Direction,
+    ) -> Result<Option<&C::Leaf>, CanonError> {
+        self.branch.save_position(&mut self.saved);
+        let found = if self.branch.step(direction) {
+            self.branch.walk(&mut AllLeaves, direction)
+        } else {
+            Ok(None)
+        };
+        self.settle(found)
+    }
+
+    /// Moves the cursor to the `nth` leaf of the collection, if any
+    ///
+    /// The walk starts from the lowest level containing the `nth` leaf.
+    pub fn seek_nth(&mut self, n: u64) -> Result<Option<&C::Leaf>, CanonError>
+    where
+        A: Borrow<Cardinality>,
+    {
+        self.branch.save_position(&mut self.saved);
+
+        let levels = self.branch.levels();
+        let parents = &levels[..levels.len() - 1];
+        let (depth, start) = containing_level::<C, A, _>(
+            parents.iter().map(|level| (&**level, level.offset())),
+            n,
+        );
+
+        self.branch.unwind_to(depth);
+        let found =
+            self.branch.walk(&mut Offset(n - start), Direction::Forward);
+        self.settle(found)
+    }
+
+    // Keeps the cursor at the leaf found by a move, or moves it back to the
+    // saved position if the move found none or failed. The nodes on the way
+    // back were loaded before the move, so moving back loads nothing new.
+    fn settle(
+        &mut self,
+        found: Result<Option<()>, CanonError>,
+    ) -> Result<Option<&C::Leaf>, CanonError> {
+        if let Ok(Some(())) = found {
+            return Ok(Some(self.current()));
+        }
+        self.branch.seek(&self.saved)?;
+        found.map(|_| None)
+    }
+}
+
+// Finds the depth of the deepest level whose node contains the nth leaf, along
+// with the index of its first leaf, given the nodes and offsets of the parent
+// levels of a branch
+fn containing_level<'b, C, A, I>(parents: I, n: u64) -> (usize, u64)
+where
+    C: 'b + Compound<A>,
+    A: Combine<C, A> + Borrow<Cardinality>,
+    I: Iterator<Item = (&'b C, usize)>,
+{
+    let mut depth = 1;
+    let mut start = 0;
+    for (parent, ofs) in parents {
+        let first = start + leaves_before::<C, A>(parent, ofs);
+        let card = match parent.child(ofs) {
+            Child::Node(node) => u64::from(node.annotation().borrow()),
+            _ => unreachable!("Levels are always nodes"),
+        };
+        if n < first || n >= first + card {
+            break;
+        }
+        depth += 1;
+        start = first;
+    }
+    (depth, start)
+}
+
+/// A mutable branch that can be moved between the leaves of a collection in
+/// place.
+///
+/// The annotations of the nodes on the branch are only recombined if a leaf
+/// below them was changed, once, when the cursor moves out of the node or is
+/// dropped. Editing a run of leaves thus costs one recombination per changed
+/// node, instead of one per edit. Like a `Cursor`, it stays where it was if a
+/// move fails.
+pub struct CursorMut<'a, C, A>
+where
+    C: Compound<A>,
+    A: Combine<C, A>,
+{
+    branch: PartialBranchMut<'a, C, A>,
+    // See `Cursor`
+    saved: Vec<usize>,
+}
+
+impl<'a, C, A> BranchMut<'a, C, A>
+where
+    C: Compound<A>,
+    A: Combine<C, A>,
+{
+    /// Turns the branch into a cursor pointing at the same leaf
+    pub fn into_cursor(self) -> CursorMut<'a, C, A> {
+        let mut branch = self.0;
+        branch.recombine_lazily();
+        CursorMut {
+            branch,
+            saved: Vec::new(),
+        }
+    }
+}
+
+impl<'a, C, A> CursorMut<'a, C, A>
+where
+    C: Compound<A>,
+    A: Combine<C, A>,
+{
+    /// Returns the leaf pointed at by the cursor
+    pub fn current(&self) -> &C::Leaf {
+        self.branch.leaf().expect("Invalid cursor")
+    }
+
+    /// Returns the leaf pointed at by the cursor mutably, marking the
+    /// annotations above it to be recombined
+    pub fn current_mut(&mut self) -> &mut C::Leaf {
+        self.branch.leaf_mut().expect("Invalid cursor")
+    }
+
+    /// Returns a slice into the levels of the tree.
+    pub fn levels(&self) -> &[LevelMut<'a, C, A>] {
+        self.branch.levels()
+    }
+
+    /// Moves the cursor to the next leaf, if any
+    #[allow(clippy::should_implement_trait)]
+    pub fn next(&mut self) -> Result<Option<&C::Leaf>, CanonError> {
+        self.step(Direction::Forward)
+    }
+
+    /// Moves the cursor to the previous leaf, if any
+    pub fn prev(&mut self) -> Result<Option<&C::Leaf>, CanonError> {
+        self.step(Direction::Backward)
+    }
+
+    fn step(
+        &mut self,
+        direction: Direction,
+    ) -> Result<Option<&C::Leaf>, CanonError> {
+        self.branch.save_position(&mut self.saved);
+        let found = if self.branch.step(direction) {
+            self.branch.walk(&mut AllLeaves, direction)
+        } else {
+            Ok(None)
+        };
+        self.settle(found)
+    }
+
+    /// Moves the cursor to the `nth` leaf of the collection, if any
+    ///
+    /// The walk starts from the lowest level containing the `nth` leaf.
+    pub fn seek_nth(&mut self, n: u64) -> Result<Option<&C::Leaf>, CanonError>
+    where
+        A: Borrow<Cardinality>,
+    {
+        self.branch.save_position(&mut self.saved);
+
+        let levels = self.branch.levels();
+        let parents = &levels[..levels.len() - 1];
+        let (depth, start) = containing_level::<C, A, _>(
+            parents.iter().map(|level| (&**level, level.offset())),
+            n,
+        );
+
+        self.branch.unwind_to(depth);
+        let found =
+            self.branch.walk(&mut Offset(n - start), Direction::Forward);
+        self.settle(found)
+    }
+
+    // See `Cursor::settle`
+    fn settle(
+        &mut self,
+        found: Result<Option<()>, CanonError>,
+    ) -> Result<Option<&C::Leaf>, CanonError> {
+        if let Ok(Some(())) = found {
+            return Ok(Some(self.current()));
+        }
+        self.branch.seek(&self.saved)?;
+        found.map(|_| None)
+    }
+}
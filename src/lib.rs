@@ -22,13 +22,42 @@ extern crate alloc;
 mod annotations;
 mod branch;
 mod branch_mut;
+mod collections;
+mod combinators;
 mod compound;
+mod cursor;
+mod link;
+mod path;
+mod reader;
+mod search;
+mod teardown;
 mod walk;
 
 pub use annotations::{
-    Annotated, Annotation, Cardinality, Combine, GetMaxKey, Keyed, MaxKey, Nth,
+    verify_annotations, verify_proof, Annotated, Annotation,
+    AnnotationMismatch, Blake2b, BottomK, ByteLen, ByteOffset, Cardinality,
+    Combine, FindKey, FindMaxKey, FindMinKey, GetKey, GetMaxKey, GetMinKey,
+    GetRange, KeyRange, Keyed, MaxKey, MerkleHasher, MerkleProof, MerkleRoot,
+    MinKey, Nth, NthByte, ProofLevel, Sum, TopK, Weighted, WeightedNth,
+};
+pub use branch::{Branch, BranchIterator, MappedBranch};
+pub use branch_mut::{
+    BranchMut, BranchMutIterator, BranchMutMapped, BranchMutMappedMut,
+};
+pub use collections::{BTreeMap, Entry, KeyPrefix, Rope, Trie, Vector};
+pub use combinators::{
+    FilterLeaves, SkipLeaves, TakeLeaves, ThenLeaves, WalkerExt,
+};
+pub use compound::{
+    Child, ChildMut, Compound, CompoundInsert, CompoundRemove, IterChild,
+    MutableLeaves, OwnedChild, SortedLeaves,
+};
+pub use cursor::{Cursor, CursorMut};
+pub use path::{BranchPath, PathError};
+pub use reader::ByteReader;
+pub use search::{Prune, Search};
+pub use teardown::teardown;
+pub use walk::{
+    walker_fn, AllLeaves, Direction, First, IterWith, Last, Step, Walk,
+    WalkChildren, Walker, WalkerFn,
 };
-pub use branch::Branch;
-pub use branch_mut::BranchMut;
-pub use compound::{Child, ChildMut, Compound, IterChild, MutableLeaves};
-pub use walk::{First, Step, Walk, Walker};
@@ -0,0 +1,137 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+//
+// Copyright (c) DUSK NETWORK. All rights reserved.
+
+use core::cell::{Ref, RefCell, RefMut};
+use core::mem;
+
+use alloc::rc::Rc;
+
+use canonical::{Canon, CanonError, Id, IdHash, Sink, Source};
+
+#[derive(Debug)]
+enum LinkInner<T> {
+    Id(Id),
+    IdValue(Id, Rc<T>),
+    Value(Rc<T>),
+}
+
+/// A link to a value that is either held in memory or in storage behind an
+/// identifier, taking the place of `canonical::Repr`.
+///
+/// Values are loaded from storage on first access, and kept in memory after.
+#[derive(Debug)]
+pub(crate) struct Link<T>(RefCell<LinkInner<T>>);
+
+impl<T> Clone for Link<T> {
+    fn clone(&self) -> Self {
+        let inner = match &*self.0.borrow() {
+            LinkInner::Id(id) => LinkInner::Id(*id),
+            LinkInner::IdValue(id, rc) => LinkInner::IdValue(*id, rc.clone()),
+            LinkInner::Value(rc) => LinkInner::Value(rc.clone()),
+        };
+        Link(RefCell::new(inner))
+    }
+}
+
+impl<T> Canon for Link<T>
+where
+    T: Canon,
+{
+    fn encode(&self, sink: &mut Sink) {
+        let id = match &*self.0.borrow() {
+            LinkInner::Id(id) | LinkInner::IdValue(id, _) => *id,
+            LinkInner::Value(rc) => Id::new(&**rc),
+        };
+        // cache the id, unless the value is borrowed
+        if let Ok(mut inner) = self.0.try_borrow_mut() {
+            if let LinkInner::Value(rc) = &*inner {
+                *inner = LinkInner::IdValue(id, rc.clone())
+            }
+        }
+        id.encode(sink)
+    }
+
+    fn decode(source: &mut Source) -> Result<Self, CanonError> {
+        Ok(Link(RefCell::new(LinkInner::Id(Id::decode(source)?))))
+    }
+
+    fn encoded_len(&self) -> usize {
+        match &*self.0.borrow() {
+            LinkInner::Id(id) | LinkInner::IdValue(id, _) => id.encoded_len(),
+            LinkInner::Value(rc) => {
+                // values longer than a hash are stored behind one
+                let len = rc.encoded_len();
+                let payload_len = core::cmp::min(len, mem::size_of::<IdHash>());
+                1 + (len as u32).encoded_len() + payload_len
+            }
+        }
+    }
+}
+
+impl<T> Link<T> {
+    /// Construct a new `Link` from value `t`
+    pub fn new(t: T) -> Self {
+        Link(RefCell::new(LinkInner::Value(Rc::new(t))))
+    }
+
+    /// Returns a reference to the value, loading it from storage if needed
+    pub fn val(&self) -> Result<Ref<'_, T>, CanonError>
+    where
+        T: Canon,
+    {
+        // only borrow mutably to load, so several references can be held
+        let id = match &*self.0.borrow() {
+            LinkInner::Id(id) => Some(*id),
+            _ => None,
+        };
+        if let Some(id) = id {
+            let val = Rc::new(id.reify()?);
+            *self.0.borrow_mut() = LinkInner::IdValue(id, val);
+        }
+        Ok(Ref::map(self.0.borrow(), |inner| match inner {
+            LinkInner::IdValue(_, rc) | LinkInner::Value(rc) => &**rc,
+            LinkInner::Id(_) => unreachable!("Loaded above"),
+        }))
+    }
+
+    /// Returns a mutable reference to the value, loading it from storage if
+    /// needed and cloning it if shared
+    pub fn val_mut(&mut self) -> Result<RefMut<'_, T>, CanonError>
+    where
+        T: Canon,
+    {
+        let inner = self.0.get_mut();
+        let rc = match inner {
+            LinkInner::Id(id) => Rc::new(id.reify()?),
+            LinkInner::IdValue(_, rc) | LinkInner::Value(rc) => rc.clone(),
+        };
+        // the cached id no longer holds once the value is changed
+        *inner = LinkInner::Value(rc);
+        Ok(RefMut::map(self.0.borrow_mut(), |inner| match inner {
+            LinkInner::Value(rc) => Rc::make_mut(rc),
+            _ => unreachable!("Set above"),
+        }))
+    }
+
+    /// Takes the value out of the link, leaving a default value, if it is held
+    /// in memory and not shared with any clone
+    pub fn take_unique(&mut self) -> Option<T>
+    where
+        T: Default,
+    {
+        let inner = self.0.get_mut();
+        let taken = match inner {
+            LinkInner::IdValue(_, rc) | LinkInner::Value(rc) => {
+                mem::take(Rc::get_mut(rc)?)
+            }
+            LinkInner::Id(_) => return None,
+        };
+        if let LinkInner::IdValue(_, rc) = inner {
+            *inner = LinkInner::Value(rc.clone())
+        }
+        Some(taken)
+    }
+}
@@ -0,0 +1,132 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+//
+// Copyright (c) DUSK NETWORK. All rights reserved.
+
+use core::convert::TryFrom;
+
+use alloc::vec::Vec;
+
+use canonical::CanonError;
+use canonical_derive::Canon;
+
+use crate::annotations::Combine;
+use crate::branch::{Branch, PartialBranch};
+use crate::branch_mut::{BranchMut, PartialBranchMut};
+use crate::compound::Compound;
+
+/// The offsets of the levels of a branch, from the root down to the leaf
+///
+/// Paths can be stored, and replayed with `Branch::from_path` and
+/// `BranchMut::from_path` to point at the same leaf of an unchanged
+/// collection.
+#[derive(Canon, Clone, Debug, Default, PartialEq, Eq, Hash)]
+pub struct BranchPath(Vec<u64>);
+
+impl BranchPath {
+    /// Returns the depth of the path
+    pub fn len(&self) -> usize {
+        self.0.len()
+    }
+
+    /// Returns true if the path has no levels
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    /// Returns an iterator over the offsets of the path, from the root down
+    pub fn offsets(&self) -> impl Iterator<Item = u64> + '_ {
+        self.0.iter().copied()
+    }
+
+    // The offsets of the path, `None` for those out of the range of `usize`,
+    // which lead past the end of any node
+    fn child_offsets(&self) -> impl Iterator<Item = Option<usize>> + '_ {
+        self.0.iter().map(|ofs| usize::try_from(*ofs).ok())
+    }
+}
+
+/// The errors when replaying a `BranchPath`
+#[derive(Debug, Clone)]
+pub enum PathError {
+    /// The path continues past the leaf it leads to
+    TooLong,
+    /// A node on the path failed to load
+    Canon(CanonError),
+}
+
+impl From<CanonError> for PathError {
+    fn from(e: CanonError) -> Self {
+        PathError::Canon(e)
+    }
+}
+
+impl From<&[usize]> for BranchPath {
+    fn from(offsets: &[usize]) -> Self {
+        BranchPath(offsets.iter().map(|ofs| *ofs as u64).collect())
+    }
+}
+
+// Rejects a path that continues past the leaf it found
+fn check_len<I>(found: Option<()>, mut rest: I) -> Result<Option<()>, PathError>
+where
+    I: Iterator,
+{
+    match (found, rest.next()) {
+        (Some(()), Some(_)) => Err(PathError::TooLong),
+        (found, _) => Ok(found),
+    }
+}
+
+impl<'a, C, A> Branch<'a, C, A>
+where
+    C: Compound<A>,
+    A: Combine<C, A>,
+{
+    /// Returns the path of the branch
+    pub fn to_path(&self) -> BranchPath {
+        BranchPath::from(&self.0.position()[..])
+    }
+
+    /// Constructs a branch following `path` from `root`
+    ///
+    /// Returns `None` if the path leads to an empty slot, past the end of a
+    /// node, or ends before reaching a leaf, and `PathError::TooLong` if it
+    /// continues past one.
+    pub fn from_path(
+        root: &'a C,
+        path: &BranchPath,
+    ) -> Result<Option<Self>, PathError> {
+        let mut offsets = path.child_offsets();
+        let mut partial = PartialBranch::new(root);
+        let found = partial.path(|| offsets.next()?)?;
+        Ok(check_len(found, offsets)?.map(|()| Branch(partial)))
+    }
+}
+
+impl<'a, C, A> BranchMut<'a, C, A>
+where
+    C: Compound<A>,
+    A: Combine<C, A>,
+{
+    /// Returns the path of the branch
+    pub fn to_path(&self) -> BranchPath {
+        BranchPath::from(&self.0.position()[..])
+    }
+
+    /// Constructs a mutable branch following `path` from `root`
+    ///
+    /// Returns `None` if the path leads to an empty slot, past the end of a
+    /// node, or ends before reaching a leaf, and `PathError::TooLong` if it
+    /// continues past one.
+    pub fn from_path(
+        root: &'a mut C,
+        path: &BranchPath,
+    ) -> Result<Option<Self>, PathError> {
+        let mut offsets = path.child_offsets();
+        let mut partial = PartialBranchMut::new(root);
+        let found = partial.path(|| offsets.next()?)?;
+        Ok(check_len(found, offsets)?.map(|()| BranchMut(partial)))
+    }
+}
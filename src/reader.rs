@@ -0,0 +1,103 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+//
+// Copyright (c) DUSK NETWORK. All rights reserved.
+
+use core::cmp;
+
+use alloc::vec::Vec;
+
+use canonical::CanonError;
+
+use crate::annotations::Combine;
+use crate::branch::{Branch, BranchIterator};
+use crate::compound::Compound;
+use crate::walk::AllLeaves;
+
+/// Reader streaming the bytes of a collection of byte chunks, from a byte in
+/// the chunk a `Branch` points to up to the end of the collection.
+///
+/// Chunks are only loaded once read from.
+pub struct ByteReader<'a, C, A>
+where
+    C: Compound<A>,
+    A: Combine<C, A>,
+{
+    chunks: BranchIterator<'a, C, A, AllLeaves>,
+    // The unread part of the current chunk
+    chunk: &'a [u8],
+    // The bytes to skip at the start of the first chunk
+    skip: usize,
+}
+
+impl<'a, C, A> Branch<'a, C, A>
+where
+    C: Compound<A>,
+    C::Leaf: AsRef<[u8]>,
+    A: Combine<C, A>,
+{
+    /// Turns the branch into a reader starting at byte `ofs` of the chunk it
+    /// points to
+    pub fn into_reader(self, ofs: usize) -> ByteReader<'a, C, A> {
+        ByteReader {
+            chunks: self.into_iter(),
+            chunk: &[],
+            skip: ofs,
+        }
+    }
+}
+
+impl<'a, C, A> ByteReader<'a, C, A>
+where
+    C: Compound<A>,
+    C::Leaf: AsRef<[u8]>,
+    A: Combine<C, A>,
+{
+    /// Reads bytes into `buf`, returning how many were read
+    ///
+    /// Fewer bytes than fit in `buf` are only read at the end of the
+    /// collection, after which 0 is returned.
+    pub fn read(&mut self, buf: &mut [u8]) -> Result<usize, CanonError> {
+        let mut read = 0;
+        while read < buf.len() {
+            if self.chunk.is_empty() {
+                match self.chunks.next() {
+                    Some(chunk) => {
+                        let chunk = chunk?.as_ref();
+                        let skip = cmp::min(self.skip, chunk.len());
+                        self.skip = 0;
+                        self.chunk = &chunk[skip..];
+                        continue;
+                    }
+                    None => break,
+                }
+            }
+
+            let n = cmp::min(buf.len() - read, self.chunk.len());
+            buf[read..read + n].copy_from_slice(&self.chunk[..n]);
+            self.chunk = &self.chunk[n..];
+            read += n;
+        }
+        Ok(read)
+    }
+
+    /// Reads all bytes up to the end of the collection, appending them to
+    /// `buf` and returning how many were read
+    pub fn read_to_end(
+        &mut self,
+        buf: &mut Vec<u8>,
+    ) -> Result<usize, CanonError> {
+        let start = buf.len();
+        buf.extend_from_slice(self.chunk);
+        self.chunk = &[];
+
+        for chunk in &mut self.chunks {
+            let chunk = chunk?.as_ref();
+            let skip = cmp::min(self.skip, chunk.len());
+            self.skip = 0;
+            buf.extend_from_slice(&chunk[skip..]);
+        }
+        Ok(buf.len() - start)
+    }
+}
@@ -0,0 +1,119 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+//
+// Copyright (c) DUSK NETWORK. All rights reserved.
+
+use crate::annotations::Combine;
+use crate::branch::BranchIterator;
+use crate::branch_mut::BranchMutIterator;
+use crate::compound::{Child, Compound, MutableLeaves};
+use crate::walk::{Step, Walk, Walker};
+
+/// Walker finding the leaves matching a predicate, only descending into the
+/// subtrees whose annotation matches another
+///
+/// The node predicate must hold for any subtree containing a matching leaf,
+/// for example a maximum key above a bound when looking for keys above it.
+pub struct Prune<N, L> {
+    node: N,
+    leaf: L,
+}
+
+impl<N, L> Prune<N, L> {
+    /// Creates a walker descending into nodes matching `node`, and finding
+    /// leaves matching `leaf`
+    pub fn new(node: N, leaf: L) -> Self {
+        Prune { node, leaf }
+    }
+}
+
+impl<C, A, N, L> Walker<C, A> for Prune<N, L>
+where
+    C: Compound<A>,
+    A: Combine<C, A>,
+    N: FnMut(&A) -> bool,
+    L: FnMut(&C::Leaf) -> bool,
+{
+    fn walk(&mut self, walk: Walk<C, A>) -> Step {
+        for i in 0.. {
+            match walk.child(i) {
+                Child::Leaf(l) => {
+                    if (self.leaf)(l) {
+                        return Step::Found(i);
+                    }
+                }
+                Child::Node(n) => {
+                    if (self.node)(n.annotation()) {
+                        return Step::Into(i);
+                    }
+                }
+                Child::Empty => (),
+                Child::EndOfNode => return Step::Advance,
+            }
+        }
+        unreachable!()
+    }
+}
+
+/// Trait that provides `search()` and `search_mut()` methods to any Compound,
+/// using its annotations as an index
+pub trait Search<'a, A>
+where
+    Self: Compound<A>,
+    A: Combine<Self, A>,
+{
+    /// Returns an iterator over the leaves matching `leaf`, in subtrees with
+    /// annotations matching `node`
+    fn search<N, L>(
+        &'a self,
+        node: N,
+        leaf: L,
+    ) -> BranchIterator<'a, Self, A, Prune<N, L>>
+    where
+        N: FnMut(&A) -> bool,
+        L: FnMut(&Self::Leaf) -> bool;
+
+    /// Returns a mutable iterator over the leaves matching `leaf`, in subtrees
+    /// with annotations matching `node`
+    fn search_mut<N, L>(
+        &'a mut self,
+        node: N,
+        leaf: L,
+    ) -> BranchMutIterator<'a, Self, A, Prune<N, L>>
+    where
+        N: FnMut(&A) -> bool,
+        L: FnMut(&Self::Leaf) -> bool,
+        Self: MutableLeaves;
+}
+
+impl<'a, C, A> Search<'a, A> for C
+where
+    C: Compound<A>,
+    A: Combine<C, A>,
+{
+    fn search<N, L>(
+        &'a self,
+        node: N,
+        leaf: L,
+    ) -> BranchIterator<'a, Self, A, Prune<N, L>>
+    where
+        N: FnMut(&A) -> bool,
+        L: FnMut(&Self::Leaf) -> bool,
+    {
+        BranchIterator::new(self, Prune::new(node, leaf))
+    }
+
+    fn search_mut<N, L>(
+        &'a mut self,
+        node: N,
+        leaf: L,
+    ) -> BranchMutIterator<'a, Self, A, Prune<N, L>>
+    where
+        N: FnMut(&A) -> bool,
+        L: FnMut(&Self::Leaf) -> bool,
+        C: MutableLeaves,
+    {
+        BranchMutIterator::new(self, Prune::new(node, leaf))
+    }
+}
@@ -0,0 +1,51 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+//
+// Copyright (c) DUSK NETWORK. All rights reserved.
+
+use alloc::vec::Vec;
+
+use crate::annotations::Annotation;
+use crate::compound::{ChildMut, Compound};
+
+/// Drops the subtrees of `node` without recursing into them.
+///
+/// Dropping a node drops its children, which for deep collections, such as
+/// long linked lists, can overflow the stack. Calling this function from the
+/// `Drop` implementation of a collection instead moves the children into a
+/// worklist, and tears them down one at a time, leaving empty nodes in their
+/// place.
+///
+/// Only subtrees held in memory and not shared with any clone are torn down,
+/// the others are left to be dropped as usual, which never recurses into
+/// them.
+pub fn teardown<C, A>(node: &mut C)
+where
+    C: Compound<A> + Default,
+    A: Annotation<C::Leaf>,
+{
+    let mut worklist = Vec::new();
+    unlink(node, &mut worklist);
+
+    while let Some(mut child) = worklist.pop() {
+        unlink(&mut child, &mut worklist);
+    }
+}
+
+// Moves the children of `node` held in memory and not shared to `worklist`
+fn unlink<C, A>(node: &mut C, worklist: &mut Vec<C>)
+where
+    C: Compound<A> + Default,
+    A: Annotation<C::Leaf>,
+{
+    for i in 0.. {
+        match node.child_mut(i) {
+            ChildMut::Node(annotated) => {
+                worklist.extend(annotated.take_unique())
+            }
+            ChildMut::EndOfNode => break,
+            _ => (),
+        }
+    }
+}
@@ -9,13 +9,14 @@ use core::marker::PhantomData;
 use canonical::CanonError;
 
 use crate::annotations::Combine;
-use crate::branch::Branch;
-use crate::branch_mut::BranchMut;
-use crate::compound::{Child, Compound, MutableLeaves};
+use crate::branch::{Branch, BranchIterator};
+use crate::branch_mut::{BranchMut, BranchMutIterator};
+use crate::compound::{Child, Compound, IterChild, MutableLeaves};
 
 /// The return value from a closure to `walk` the tree.
 ///
-/// Determines how the `Branch` is constructed
+/// Determines how the `Branch` is constructed. Offsets are relative to the
+/// `Walk`, and count in the direction of the walk.
 pub enum Step {
     /// The correct leaf was found!
     Found(usize),
@@ -27,10 +28,21 @@ pub enum Step {
     Abort,
 }
 
+/// The direction in which the children of a node are visited
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Direction {
+    /// From the first child to the last
+    Forward,
+    /// From the last child to the first
+    Backward,
+}
+
 /// The argument given to a `Walker` to traverse through nodes.
 pub struct Walk<'a, C, A> {
     ofs: usize,
     compound: &'a C,
+    direction: Direction,
+    depth: usize,
     _marker: PhantomData<A>,
 }
 
@@ -39,20 +51,130 @@ where
     C: Compound<A>,
     A: Combine<C, A>,
 {
-    pub(crate) fn new(compound: &'a C, ofs: usize) -> Self {
+    pub(crate) fn new(
+        compound: &'a C,
+        ofs: usize,
+        direction: Direction,
+        depth: usize,
+    ) -> Self {
         Walk {
             ofs,
             compound,
+            direction,
+            depth,
             _marker: PhantomData,
         }
     }
 
     /// Returns the child at specific offset relative to the branch offset
+    ///
+    /// When walking backward, offsets count towards the first child, and any
+    /// offset past it is the end of the node.
     pub fn child(&self, ofs: usize) -> Child<'a, C, A> {
-        self.compound.child(ofs + self.ofs)
+        match self.node_offset(ofs) {
+            Some(ofs) => self.compound.child(ofs),
+            None => Child::EndOfNode,
+        }
+    }
+
+    /// Returns the offset in the node of the child at `ofs` relative to the
+    /// walk, or `None` if that is past the first child when walking backward
+    pub fn node_offset(&self, ofs: usize) -> Option<usize> {
+        match self.direction {
+            Direction::Forward => Some(ofs + self.ofs),
+            Direction::Backward => self.ofs.checked_sub(ofs),
+        }
+    }
+
+    /// Returns the direction of the walk
+    pub fn direction(&self) -> Direction {
+        self.direction
+    }
+
+    /// Returns the depth of the walked node in the branch, the root being at
+    /// depth 0
+    pub fn depth(&self) -> usize {
+        self.depth
+    }
+
+    /// Returns the walk starting `n` children further in its direction, or
+    /// `None` if that is past the first child when walking backward
+    pub(crate) fn skip_children(&self, n: usize) -> Option<Self> {
+        let ofs = match self.direction {
+            Direction::Forward => self.ofs + n,
+            Direction::Backward => self.ofs.checked_sub(n)?,
+        };
+        Some(Walk::new(self.compound, ofs, self.direction, self.depth))
+    }
+
+    /// Returns an iterator over the non-empty children of the walk, together
+    /// with their offsets relative to the branch offset
+    pub fn children(&self) -> WalkChildren<'a, C, A> {
+        WalkChildren {
+            walk: Walk::new(
+                self.compound,
+                self.ofs,
+                self.direction,
+                self.depth,
+            ),
+            ofs: 0,
+        }
     }
 }
 
+/// Iterator over the children of a `Walk`, skipping empty slots
+pub struct WalkChildren<'a, C, A> {
+    walk: Walk<'a, C, A>,
+    ofs: usize,
+}
+
+impl<'a, C, A> Iterator for WalkChildren<'a, C, A>
+where
+    C: Compound<A>,
+    C::Leaf: 'a,
+    A: Combine<C, A> + 'a,
+{
+    type Item = (usize, IterChild<'a, C, A>);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            let ofs = self.ofs;
+            self.ofs += 1;
+            match self.walk.child(ofs) {
+                Child::Leaf(l) => return Some((ofs, IterChild::Leaf(l))),
+                Child::Node(n) => return Some((ofs, IterChild::Node(n))),
+                Child::Empty => (),
+                Child::EndOfNode => return None,
+            }
+        }
+    }
+}
+
+impl<'a, C, A> Clone for Walk<'a, C, A> {
+    fn clone(&self) -> Self {
+        Walk {
+            ofs: self.ofs,
+            compound: self.compound,
+            direction: self.direction,
+            depth: self.depth,
+            _marker: PhantomData,
+        }
+    }
+}
+
+/// Returns the offset of the last child of a node, or 0 if it has none
+pub(crate) fn last_offset<C, A>(compound: &C) -> usize
+where
+    C: Compound<A>,
+    A: Combine<C, A>,
+{
+    let mut count = 0;
+    while !matches!(compound.child(count), Child::EndOfNode) {
+        count += 1;
+    }
+    count.saturating_sub(1)
+}
+
 /// The trait used to construct a `Branch` or to iterate through a tree.
 pub trait Walker<C, A>
 where
@@ -63,6 +185,48 @@ where
     fn walk(&mut self, walk: Walk<C, A>) -> Step;
 }
 
+// A walker can be lent out, to keep its state after the walk
+impl<C, A, W> Walker<C, A> for &mut W
+where
+    C: Compound<A>,
+    A: Combine<C, A>,
+    W: Walker<C, A>,
+{
+    fn walk(&mut self, walk: Walk<C, A>) -> Step {
+        (**self).walk(walk)
+    }
+}
+
+/// Walker created from a closure, see `walker_fn`
+pub struct WalkerFn<C, A, F> {
+    f: F,
+    _marker: PhantomData<(C, A)>,
+}
+
+/// Creates a `Walker` from a closure, for one-off walks
+pub fn walker_fn<C, A, F>(f: F) -> WalkerFn<C, A, F>
+where
+    C: Compound<A>,
+    A: Combine<C, A>,
+    F: FnMut(Walk<C, A>) -> Step,
+{
+    WalkerFn {
+        f,
+        _marker: PhantomData,
+    }
+}
+
+impl<C, A, F> Walker<C, A> for WalkerFn<C, A, F>
+where
+    C: Compound<A>,
+    A: Combine<C, A>,
+    F: FnMut(Walk<C, A>) -> Step,
+{
+    fn walk(&mut self, walk: Walk<C, A>) -> Step {
+        (self.f)(walk)
+    }
+}
+
 /// Walker that visits all leaves
 pub struct AllLeaves;
 
@@ -121,3 +285,85 @@ where
         BranchMut::<_, A>::walk(self, AllLeaves)
     }
 }
+
+/// Trait that provides a `last` and `last_mut` method to any Compound
+pub trait Last<'a, A>
+where
+    Self: Compound<A>,
+    A: Combine<Self, A>,
+{
+    /// Construct a `Branch` pointing to the last element, if not empty
+    fn last(&'a self) -> Result<Option<Branch<'a, Self, A>>, CanonError>;
+
+    /// Construct a `BranchMut` pointing to the last element, if not empty
+    fn last_mut(
+        &'a mut self,
+    ) -> Result<Option<BranchMut<'a, Self, A>>, CanonError>
+    where
+        Self: MutableLeaves;
+}
+
+impl<'a, C, A> Last<'a, A> for C
+where
+    C: Compound<A> + Clone,
+    A: Combine<C, A>,
+{
+    fn last(&'a self) -> Result<Option<Branch<'a, Self, A>>, CanonError> {
+        Branch::<_, A>::walk_back(self, AllLeaves)
+    }
+
+    fn last_mut(
+        &'a mut self,
+    ) -> Result<Option<BranchMut<'a, Self, A>>, CanonError>
+    where
+        C: MutableLeaves,
+    {
+        BranchMut::<_, A>::walk_back(self, AllLeaves)
+    }
+}
+
+/// Trait that provides `iter_with` and `iter_mut_with` methods to any
+/// Compound, to iterate over the leaves found by a `Walker`
+pub trait IterWith<'a, A>
+where
+    Self: Compound<A>,
+    A: Combine<Self, A>,
+{
+    /// Returns an iterator over the leaves found by `walker`
+    fn iter_with<W>(&'a self, walker: W) -> BranchIterator<'a, Self, A, W>
+    where
+        W: Walker<Self, A>;
+
+    /// Returns an iterator over the mutable leaves found by `walker`
+    fn iter_mut_with<W>(
+        &'a mut self,
+        walker: W,
+    ) -> BranchMutIterator<'a, Self, A, W>
+    where
+        W: Walker<Self, A>,
+        Self: MutableLeaves;
+}
+
+impl<'a, C, A> IterWith<'a, A> for C
+where
+    C: Compound<A>,
+    A: Combine<C, A>,
+{
+    fn iter_with<W>(&'a self, walker: W) -> BranchIterator<'a, Self, A, W>
+    where
+        W: Walker<Self, A>,
+    {
+        BranchIterator::new(self, walker)
+    }
+
+    fn iter_mut_with<W>(
+        &'a mut self,
+        walker: W,
+    ) -> BranchMutIterator<'a, Self, A, W>
+    where
+        W: Walker<Self, A>,
+        C: MutableLeaves,
+    {
+        BranchMutIterator::new(self, walker)
+    }
+}
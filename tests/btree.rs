@@ -0,0 +1,162 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+//
+// Copyright (c) DUSK NETWORK. All rights reserved.
+
+use std::collections::BTreeMap as StdBTreeMap;
+
+use microkelvin::{
+    verify_annotations, BTreeMap, Branch, Cardinality, FindKey, First, Last,
+    MaxKey,
+};
+use rand::{prelude::SliceRandom, thread_rng, Rng};
+
+type Map = BTreeMap<u64, u64, (Cardinality, MaxKey<u64>)>;
+
+fn map(keys: &[u64]) -> Map {
+    let mut map = Map::new();
+    for key in keys {
+        assert_eq!(map.insert(*key, key * 10).unwrap(), None);
+    }
+    map
+}
+
+#[test]
+fn insert_get() {
+    let mut keys: Vec<u64> = (0..1000).map(|i| i * 2).collect();
+    keys.shuffle(&mut thread_rng());
+
+    let map = map(&keys);
+
+    for key in keys {
+        assert_eq!(*map.get(&key).unwrap().unwrap(), key * 10);
+        assert!(map.get(&(key + 1)).unwrap().is_none());
+    }
+}
+
+#[test]
+fn several_gets() {
+    let keys: Vec<u64> = (0..100).collect();
+    let map = map(&keys);
+
+    let a = map.get(&1).unwrap().unwrap();
+    let b = map.get(&2).unwrap().unwrap();
+    let c = map.get(&99).unwrap().unwrap();
+
+    assert_eq!((*a, *b, *c), (10, 20, 990));
+}
+
+#[test]
+fn insert_replaces() {
+    let mut map = map(&[1, 2, 3]);
+
+    assert_eq!(map.insert(2, 7).unwrap(), Some(20));
+    assert_eq!(*map.get(&2).unwrap().unwrap(), 7);
+}
+
+#[test]
+fn get_mut() {
+    let keys: Vec<u64> = (0..200).collect();
+    let mut map = map(&keys);
+
+    for key in &keys {
+        *map.get_mut(key).unwrap().unwrap() += 1;
+    }
+    assert!(map.get_mut(&200).unwrap().is_none());
+
+    for key in &keys {
+        assert_eq!(*map.get(key).unwrap().unwrap(), key * 10 + 1);
+    }
+    assert!(verify_annotations(&map).unwrap().is_empty());
+}
+
+#[test]
+fn range() {
+    let mut keys: Vec<u64> = (0..500).map(|i| i * 3).collect();
+    keys.shuffle(&mut thread_rng());
+
+    let map = map(&keys);
+
+    let found: Vec<u64> = map
+        .range(100..200)
+        .map(|entry| *entry.unwrap().key())
+        .collect();
+    let expected: Vec<u64> = (100..200).filter(|k| k % 3 == 0).collect();
+    assert_eq!(found, expected);
+
+    let found: Vec<u64> = map
+        .range(..=9)
+        .rev()
+        .map(|entry| *entry.unwrap().value())
+        .collect();
+    assert_eq!(found, vec![90, 60, 30, 0]);
+}
+
+#[test]
+fn remove() {
+    let mut keys: Vec<u64> = (0..1000).collect();
+    keys.shuffle(&mut thread_rng());
+
+    let mut map = map(&keys);
+    keys.shuffle(&mut thread_rng());
+
+    for (i, key) in keys.iter().enumerate() {
+        assert_eq!(map.remove(key).unwrap(), Some(key * 10));
+        assert_eq!(map.remove(key).unwrap(), None);
+
+        if i % 100 == 0 {
+            for key in &keys[i + 1..] {
+                assert_eq!(*map.get(key).unwrap().unwrap(), key * 10);
+            }
+        }
+    }
+
+    assert!(map.is_empty());
+}
+
+#[test]
+fn against_std() {
+    let mut rng = thread_rng();
+    let mut map = Map::new();
+    let mut model = StdBTreeMap::new();
+
+    for i in 0..5000 {
+        let key = rng.gen_range(0..500);
+        if rng.gen_bool(0.4) {
+            assert_eq!(map.remove(&key).unwrap(), model.remove(&key));
+        } else {
+            assert_eq!(map.insert(key, i).unwrap(), model.insert(key, i));
+        }
+    }
+
+    let entries: Vec<(u64, u64)> = map
+        .range(..)
+        .map(|entry| {
+            let entry = entry.unwrap();
+            (*entry.key(), *entry.value())
+        })
+        .collect();
+    let expected: Vec<(u64, u64)> = model.into_iter().collect();
+    assert_eq!(entries, expected);
+
+    assert!(verify_annotations(&map).unwrap().is_empty());
+}
+
+#[test]
+fn balanced() {
+    let mut keys: Vec<u64> = (0..2000).collect();
+    keys.shuffle(&mut thread_rng());
+
+    let mut map = map(&keys);
+    for key in &keys[..1500] {
+        map.remove(key).unwrap();
+    }
+
+    let depth = map.first().unwrap().unwrap().depth();
+    assert_eq!(map.last().unwrap().unwrap().depth(), depth);
+    for key in &keys[1500..] {
+        let branch = Branch::walk(&map, FindKey::new(key)).unwrap().unwrap();
+        assert_eq!(branch.depth(), depth);
+    }
+}
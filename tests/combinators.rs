@@ -0,0 +1,155 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+//
+// Copyright (c) DUSK NETWORK. All rights reserved.
+
+mod linked_list;
+use linked_list::LinkedList;
+
+mod tree;
+use tree::{balanced, tree};
+
+use microkelvin::{
+    walker_fn, AllLeaves, BranchIterator, BranchMutIterator, Cardinality,
+    Child, KeyRange, MaxKey, Nth, SortedLeaves, Step, Walk, Walker, WalkerExt,
+};
+
+impl<T, A> SortedLeaves for LinkedList<T, A> {}
+
+fn sorted_list(n: u64) -> LinkedList<u64, MaxKey<u64>> {
+    let mut list = LinkedList::new();
+
+    // inserted in reverse to keep the list sorted
+    for i in (0..n).rev() {
+        list.insert(i);
+    }
+
+    list
+}
+
+fn leaves<W>(list: &LinkedList<u64, MaxKey<u64>>, walker: W) -> Vec<u64>
+where
+    W: Walker<LinkedList<u64, MaxKey<u64>>, MaxKey<u64>>,
+{
+    BranchIterator::new(list, walker)
+        .map(|leaf| *leaf.unwrap())
+        .collect()
+}
+
+#[test]
+fn all_leaves() {
+    let list = sorted_list(16);
+
+    assert_eq!(leaves(&list, AllLeaves), (0..16).collect::<Vec<_>>());
+    assert_eq!(leaves(&LinkedList::new(), AllLeaves), []);
+}
+
+#[test]
+fn filter() {
+    let list = sorted_list(32);
+
+    assert_eq!(
+        leaves(
+            &list,
+            AllLeaves.filter_leaves(|l: &u64| l.is_multiple_of(5))
+        ),
+        [0, 5, 10, 15, 20, 25, 30]
+    );
+
+    assert_eq!(leaves(&list, AllLeaves.filter_leaves(|_: &u64| false)), []);
+
+    let rev: Vec<u64> = BranchIterator::new(
+        &list,
+        AllLeaves.filter_leaves(|l: &u64| l % 10 == 1),
+    )
+    .rev()
+    .map(|leaf| *leaf.unwrap())
+    .collect();
+    assert_eq!(rev, [31, 21, 11, 1]);
+}
+
+#[test]
+fn take_and_skip() {
+    let list = sorted_list(32);
+
+    assert_eq!(leaves(&list, AllLeaves.take_leaves(3)), [0, 1, 2]);
+    assert_eq!(leaves(&list, AllLeaves.take_leaves(0)), []);
+    assert_eq!(leaves(&list, AllLeaves.take_leaves(100)).len(), 32);
+
+    assert_eq!(leaves(&list, AllLeaves.skip_leaves(29)), [29, 30, 31]);
+    assert_eq!(leaves(&list, AllLeaves.skip_leaves(32)), []);
+
+    // a page of the odd leaves
+    let page = AllLeaves
+        .filter_leaves(|l: &u64| l % 2 == 1)
+        .skip_leaves(4)
+        .take_leaves(3);
+    assert_eq!(leaves(&list, page), [9, 11, 13]);
+}
+
+#[test]
+fn then() {
+    let list = sorted_list(64);
+
+    // once the first range is passed, the second one picks up the walk
+    let walker = KeyRange::new(..3).then_leaves(KeyRange::new(40..43));
+    assert_eq!(leaves(&list, walker), [0, 1, 2, 40, 41, 42]);
+
+    let walker = AllLeaves.take_leaves(2).then_leaves(AllLeaves);
+    assert_eq!(leaves(&list, walker).len(), 64);
+
+    // the second walker does not start over, so it finds nothing after a
+    // first one going through the whole list
+    let walker = AllLeaves
+        .filter_leaves(|l: &u64| *l < 3)
+        .then_leaves(AllLeaves);
+    assert_eq!(leaves(&list, walker), [0, 1, 2]);
+}
+
+#[test]
+fn then_after_advance() {
+    let tree = tree::<Cardinality>(
+        vec![Some(0), Some(1), Some(2)],
+        vec![balanced(&[3, 4, 5, 6])],
+    );
+
+    // only finds the leaves of the root, advancing past it at the first node
+    let root_leaves =
+        walker_fn(|walk: Walk<_, Cardinality>| match walk.child(0) {
+            Child::Leaf(_) => Step::Found(0),
+            _ => Step::Advance,
+        });
+
+    let found: Vec<u64> =
+        BranchIterator::new(&tree, root_leaves.then_leaves(AllLeaves))
+            .map(|leaf| *leaf.unwrap())
+            .collect();
+    assert_eq!(found, [0, 1, 2, 3, 4, 5, 6]);
+}
+
+#[test]
+fn mutable() {
+    let n: u64 = 32;
+
+    let mut list = LinkedList::<u64, Cardinality>::new();
+
+    for i in 0..n {
+        list.insert(i)
+    }
+
+    let walker = AllLeaves.filter_leaves(|l: &u64| l.is_multiple_of(4));
+    for leaf in BranchMutIterator::new(&mut list, walker) {
+        *leaf.unwrap() = 1000;
+    }
+
+    for i in 0..n {
+        let leaf = *list.nth(i).unwrap().unwrap();
+        let original = n - i - 1;
+        if original.is_multiple_of(4) {
+            assert_eq!(leaf, 1000);
+        } else {
+            assert_eq!(leaf, original);
+        }
+    }
+}
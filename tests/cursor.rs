@@ -0,0 +1,293 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+//
+// Copyright (c) DUSK NETWORK. All rights reserved.
+
+use core::borrow::Borrow;
+use std::cell::Cell;
+
+use rand::{thread_rng, Rng};
+
+mod linked_list;
+use linked_list::LinkedList;
+
+mod tree;
+use tree::{balanced, tree, Tree};
+
+use canonical::{Canon, EncodeToVec, Source};
+use canonical_derive::Canon;
+use microkelvin::{
+    AllLeaves, Annotated, Annotation, BranchMut, Cardinality, Combine,
+    Compound, First, Last, Nth,
+};
+
+#[test]
+fn scroll_list() {
+    let n: u64 = 64;
+
+    let mut list = LinkedList::<_, Cardinality>::new();
+
+    for i in (0..n).rev() {
+        list.insert(i)
+    }
+
+    let mut cursor = list.first().unwrap().unwrap().into_cursor();
+    assert_eq!(*cursor.current(), 0);
+
+    for i in 1..n {
+        assert_eq!(cursor.next().unwrap(), Some(&i));
+    }
+    assert_eq!(cursor.next().unwrap(), None);
+    assert_eq!(*cursor.current(), n - 1);
+
+    for i in (0..n - 1).rev() {
+        assert_eq!(cursor.prev().unwrap(), Some(&i));
+    }
+    assert_eq!(cursor.prev().unwrap(), None);
+    assert_eq!(*cursor.current(), 0);
+}
+
+#[test]
+fn scroll_tree() {
+    let n: u64 = 1000;
+
+    let leaves: Vec<u64> = (0..n).collect();
+    let tree = balanced::<Cardinality>(&leaves);
+
+    let mut cursor = tree.last().unwrap().unwrap().into_cursor();
+    assert_eq!(*cursor.current(), n - 1);
+
+    for i in (0..n - 1).rev() {
+        assert_eq!(cursor.prev().unwrap(), Some(&i));
+    }
+    assert_eq!(cursor.prev().unwrap(), None);
+
+    for i in 1..n {
+        assert_eq!(cursor.next().unwrap(), Some(&i));
+    }
+    assert_eq!(cursor.next().unwrap(), None);
+    assert_eq!(*cursor.current(), n - 1);
+}
+
+#[test]
+fn seek_nth() {
+    let n: u64 = 1000;
+
+    let leaves: Vec<u64> = (0..n).map(|i| i * 3).collect();
+    let tree = balanced::<Cardinality>(&leaves);
+
+    let mut cursor = tree.nth(500).unwrap().unwrap().into_cursor();
+    let depth = cursor.levels().len();
+
+    let mut rng = thread_rng();
+    for _ in 0..200 {
+        let i = rng.gen_range(0..n);
+        assert_eq!(cursor.seek_nth(i).unwrap(), Some(&(i * 3)));
+        assert_eq!(cursor.levels().len(), depth);
+    }
+
+    // seeking nearby leaves and scrolling can be mixed
+    assert_eq!(cursor.seek_nth(10).unwrap(), Some(&30));
+    assert_eq!(cursor.next().unwrap(), Some(&33));
+    assert_eq!(cursor.seek_nth(12).unwrap(), Some(&36));
+    assert_eq!(cursor.prev().unwrap(), Some(&33));
+
+    // out of range seeks leave the cursor in place
+    assert_eq!(cursor.seek_nth(n).unwrap(), None);
+    assert_eq!(*cursor.current(), 33);
+    assert_eq!(cursor.next().unwrap(), Some(&36));
+}
+
+// A tree with the leaf 0, a node failing to load, its encoding pointing at a
+// value missing from the store, and the leaf 5 in a node behind it
+fn with_missing_node() -> Tree<Cardinality> {
+    let node =
+        Annotated::new(tree::<Cardinality>(vec![Some(u64::MAX); 4], vec![]));
+    let mut bytes = node.encode_to_vec();
+
+    // the hash of the stored node closes its id, ahead of the annotation
+    let end = bytes.len() - node.annotation().encoded_len();
+    for byte in &mut bytes[end - 32..end] {
+        *byte = !*byte;
+    }
+    let missing = Annotated::decode(&mut Source::new(&bytes)).unwrap();
+
+    Tree {
+        leaves: vec![Some(0)],
+        nodes: vec![missing, Annotated::new(tree(vec![Some(5)], vec![]))],
+    }
+}
+
+#[test]
+fn failed_loads() {
+    let tree = with_missing_node();
+    let mut cursor = tree.first().unwrap().unwrap().into_cursor();
+
+    assert!(cursor.next().is_err());
+    assert_eq!(*cursor.current(), 0);
+
+    let tree = with_missing_node();
+    let mut cursor = tree.last().unwrap().unwrap().into_cursor();
+
+    assert!(cursor.seek_nth(2).is_err());
+    assert_eq!(*cursor.current(), 5);
+    assert_eq!(cursor.seek_nth(0).unwrap(), Some(&0));
+}
+
+#[test]
+fn failed_loads_mut() {
+    let mut tree = with_missing_node();
+    let mut cursor = tree.first_mut().unwrap().unwrap().into_cursor();
+
+    *cursor.current_mut() = 10;
+    assert!(cursor.next().is_err());
+    assert_eq!(*cursor.current_mut(), 10);
+    drop(cursor);
+    assert_eq!(*tree.first().unwrap().unwrap(), 10);
+
+    let mut tree = with_missing_node();
+    let mut cursor = tree.last_mut().unwrap().unwrap().into_cursor();
+
+    assert!(cursor.seek_nth(2).is_err());
+    assert_eq!(*cursor.current(), 5);
+    assert_eq!(cursor.seek_nth(0).unwrap(), Some(&0));
+}
+
+thread_local! {
+    static COMBINED: Cell<usize> = const { Cell::new(0) };
+}
+
+fn combined() -> usize {
+    COMBINED.with(|combined| combined.replace(0))
+}
+
+// Cardinality and maximum leaf, counting how often it is combined
+#[derive(Clone, Canon, Debug, Default)]
+struct Counted {
+    cardinality: Cardinality,
+    max: u64,
+}
+
+impl Borrow<Cardinality> for Counted {
+    fn borrow(&self) -> &Cardinality {
+        &self.cardinality
+    }
+}
+
+impl Annotation<u64> for Counted {
+    fn from_leaf(leaf: &u64) -> Self {
+        Counted {
+            cardinality: Cardinality::from_leaf(leaf),
+            max: *leaf,
+        }
+    }
+}
+
+impl<C> Combine<C, Counted> for Counted
+where
+    C: Compound<Counted, Leaf = u64>,
+{
+    fn combine(node: &C) -> Self {
+        COMBINED.with(|combined| combined.set(combined.get() + 1));
+
+        let max = node
+            .children()
+            .map(|child| child.annotation().max)
+            .max()
+            .unwrap_or_default();
+
+        Counted {
+            cardinality: Cardinality::combine(node),
+            max,
+        }
+    }
+}
+
+fn max(tree: &Tree<Counted>) -> u64 {
+    let max = Counted::combine(tree).max;
+    combined();
+    max
+}
+
+#[test]
+fn edit_lazily() {
+    let n: u64 = 256;
+
+    let leaves: Vec<u64> = (0..n).collect();
+    let mut tree = balanced::<Counted>(&leaves);
+    // 4 + 16 + 64 nodes below the root
+    let nodes = 84;
+    combined();
+
+    // moving around and reading does not recombine
+    let mut cursor = tree.first_mut().unwrap().unwrap().into_cursor();
+    while cursor.next().unwrap().is_some() {}
+    assert_eq!(cursor.seek_nth(100).unwrap(), Some(&100));
+    drop(cursor);
+    // including the walk of the branch the cursor was made from
+    assert_eq!(combined(), 0);
+
+    // every node on the path of a single edit is recombined once
+    let mut cursor = tree.nth_mut(100).unwrap().unwrap().into_cursor();
+    *cursor.current_mut() = 1000;
+    cursor.next().unwrap();
+    cursor.prev().unwrap();
+    assert_eq!(cursor.seek_nth(255).unwrap(), Some(&255));
+    assert_eq!(combined(), 3);
+    drop(cursor);
+    assert_eq!(combined(), 0);
+    assert_eq!(max(&tree), 1000);
+
+    // editing every leaf recombines every node once
+    let mut cursor = tree.first_mut().unwrap().unwrap().into_cursor();
+    loop {
+        *cursor.current_mut() += n;
+        if cursor.next().unwrap().is_none() {
+            break;
+        }
+    }
+    drop(cursor);
+    assert_eq!(combined(), nodes);
+    assert_eq!(max(&tree), 1000 + n);
+
+    // dropping the cursor recombines the pending edits
+    let mut cursor = tree.nth_mut(100).unwrap().unwrap().into_cursor();
+    *cursor.current_mut() = 0;
+    drop(cursor);
+    assert_eq!(combined(), 3);
+    assert_eq!(max(&tree), 2 * n - 1);
+
+    for i in 0..n {
+        let expected = if i == 100 { 0 } else { i + n };
+        assert_eq!(*tree.nth(i).unwrap().unwrap(), expected);
+    }
+}
+
+#[test]
+fn branches_recombine() {
+    let n: u64 = 256;
+
+    let leaves: Vec<u64> = (0..n).collect();
+    let mut tree = balanced::<Counted>(&leaves);
+    combined();
+
+    // branches other than cursors recombine every node on their path
+    assert_eq!(*tree.nth_mut(7).unwrap().unwrap(), 7);
+    assert_eq!(combined(), 3);
+
+    *tree.nth_mut(7).unwrap().unwrap() = 1000;
+    assert_eq!(combined(), 3);
+    assert_eq!(max(&tree), 1000);
+
+    *BranchMut::walk(&mut tree, AllLeaves).unwrap().unwrap() = 2000;
+    assert_eq!(combined(), 3);
+    assert_eq!(max(&tree), 2000);
+
+    // and iterators every node they move through
+    for leaf in tree.first_mut().unwrap().unwrap() {
+        *leaf.unwrap() += 3000;
+    }
+    assert_eq!(combined(), 84);
+    assert_eq!(max(&tree), 5000);
+}
@@ -0,0 +1,85 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+//
+// Copyright (c) DUSK NETWORK. All rights reserved.
+
+use rand::{prelude::SliceRandom, thread_rng};
+
+mod linked_list;
+use linked_list::LinkedList;
+
+use canonical_derive::Canon;
+use microkelvin::{
+    Cardinality, GetMaxKey, GetMinKey, Keyed, MaxKey, MinKey, Nth,
+};
+use microkelvin_derive::Annotation;
+
+#[derive(Default, Clone, Canon, Annotation)]
+struct Anno<K> {
+    max: MaxKey<K>,
+    min: MinKey<K>,
+    card: Cardinality,
+}
+
+#[derive(Default, Clone, Canon, Annotation)]
+struct TupleAnno(Cardinality, MaxKey<u64>);
+
+#[derive(PartialEq, Clone, Canon, Debug)]
+struct TestLeaf {
+    key: u64,
+    other: (),
+}
+
+impl Keyed<u64> for TestLeaf {
+    fn key(&self) -> &u64 {
+        &self.key
+    }
+}
+
+#[test]
+fn derived_annotation() {
+    let n: u64 = 1024;
+
+    let mut keys = vec![];
+
+    for i in 0..n {
+        keys.push(i)
+    }
+
+    keys.shuffle(&mut thread_rng());
+
+    let mut list = LinkedList::<_, Anno<u64>>::new();
+
+    for key in keys.iter() {
+        list.insert(TestLeaf {
+            key: *key,
+            other: (),
+        });
+    }
+
+    assert_eq!(list.max_key().unwrap().unwrap().key, n - 1);
+    assert_eq!(list.min_key().unwrap().unwrap().key, 0);
+
+    // the list is in reverse insertion order
+    for i in 0..n {
+        assert_eq!(
+            list.nth(i).unwrap().unwrap().key,
+            keys[(n - i - 1) as usize]
+        );
+    }
+}
+
+#[test]
+fn derived_tuple_struct_annotation() {
+    let n: u64 = 1024;
+
+    let mut list = LinkedList::<_, TupleAnno>::new();
+
+    for i in 0..n {
+        list.insert(i);
+    }
+
+    assert_eq!(*list.max_key().unwrap().unwrap(), n - 1);
+    assert_eq!(*list.nth(0).unwrap().unwrap(), n - 1);
+}
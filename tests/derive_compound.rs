@@ -0,0 +1,131 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+//
+// Copyright (c) DUSK NETWORK. All rights reserved.
+
+use canonical::Canon;
+use canonical_derive::Canon;
+use microkelvin::{
+    Annotated, Cardinality, Child, Combine, Compound, First, MutableLeaves, Nth,
+};
+use microkelvin_derive::Compound;
+
+#[derive(Clone, Canon, Debug, Default, Compound)]
+pub enum List<T, A> {
+    #[default]
+    Empty,
+    Node {
+        #[leaf]
+        val: T,
+        #[node]
+        next: Annotated<Self, A>,
+    },
+}
+
+impl<T, A> MutableLeaves for List<T, A> {}
+
+impl<T, A> List<T, A>
+where
+    T: Canon,
+    A: Canon + Combine<Self, A>,
+{
+    fn push(&mut self, t: T) {
+        let old = core::mem::take(self);
+        *self = List::Node {
+            val: t,
+            next: Annotated::new(old),
+        };
+    }
+}
+
+#[derive(Clone, Canon, Debug, Default, Compound)]
+pub struct Wide<T, A> {
+    #[leaf]
+    first: Option<T>,
+    #[leaf]
+    leaves: Vec<T>,
+    #[node]
+    nodes: Vec<Annotated<Self, A>>,
+    #[leaf]
+    last: [Option<T>; 2],
+    // not a child
+    _tag: u8,
+}
+
+impl<T, A> MutableLeaves for Wide<T, A> {}
+
+#[derive(Clone, Canon, Debug, Compound)]
+pub struct Pair<T>(#[leaf] T, #[leaf] T);
+
+impl<T> MutableLeaves for Pair<T> {}
+
+#[test]
+fn derived_linked_list() {
+    let n: u64 = 256;
+
+    let mut list = List::<_, Cardinality>::default();
+
+    for i in 0..n {
+        list.push(i)
+    }
+
+    for i in 0..n {
+        assert_eq!(*list.nth(i).unwrap().unwrap(), n - i - 1)
+    }
+
+    for leaf in list.first_mut().unwrap().unwrap() {
+        *leaf.unwrap() *= 2;
+    }
+
+    for i in 0..n {
+        assert_eq!(*list.nth(i).unwrap().unwrap(), (n - i - 1) * 2)
+    }
+}
+
+#[test]
+fn derived_slots() {
+    let inner = Wide::<u64, Cardinality> {
+        first: Some(10),
+        leaves: vec![11, 12],
+        nodes: vec![],
+        last: [None, None],
+        _tag: 0,
+    };
+
+    let wide = Wide::<u64, Cardinality> {
+        first: None,
+        leaves: vec![0, 1, 2],
+        nodes: vec![Annotated::new(inner.clone()), Annotated::new(inner)],
+        last: [None, Some(3)],
+        _tag: 0,
+    };
+
+    assert!(matches!(wide.child(0), Child::Empty));
+    assert!(matches!(wide.child(1), Child::Leaf(0)));
+    assert!(matches!(wide.child(3), Child::Leaf(2)));
+    assert!(matches!(wide.child(4), Child::Node(_)));
+    assert!(matches!(wide.child(5), Child::Node(_)));
+    assert!(matches!(wide.child(6), Child::Empty));
+    assert!(matches!(wide.child(7), Child::Leaf(3)));
+    assert!(matches!(wide.child(8), Child::EndOfNode));
+
+    let expected = [0, 1, 2, 10, 11, 12, 10, 11, 12, 3];
+
+    for (i, expected) in expected.iter().enumerate() {
+        assert_eq!(*wide.nth(i as u64).unwrap().unwrap(), *expected);
+    }
+
+    assert!(wide.nth(expected.len() as u64).unwrap().is_none());
+}
+
+#[test]
+fn derived_without_nodes() {
+    let mut pair = Pair(1u64, 2u64);
+
+    assert_eq!(*Nth::<Cardinality>::nth(&pair, 1).unwrap().unwrap(), 2);
+
+    *Nth::<Cardinality>::nth_mut(&mut pair, 0).unwrap().unwrap() = 3;
+
+    assert_eq!(pair.0, 3);
+}
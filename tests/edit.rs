@@ -0,0 +1,124 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+//
+// Copyright (c) DUSK NETWORK. All rights reserved.
+
+mod linked_list;
+use linked_list::LinkedList;
+
+mod tree;
+use tree::{tree, Tree};
+
+use canonical::CanonError;
+use microkelvin::{
+    Annotation, Cardinality, CompoundInsert, CompoundRemove, First, GetMaxKey,
+    MaxKey, Nth, OwnedChild,
+};
+
+type Ann = (Cardinality, MaxKey<u64>);
+
+impl CompoundInsert<Ann> for Tree<Ann> {
+    fn insert_leaf(&mut self, ofs: usize, leaf: u64) -> Result<(), CanonError>
+    where
+        Ann: Annotation<u64>,
+    {
+        self.leaves.insert(ofs, Some(leaf));
+        Ok(())
+    }
+}
+
+impl CompoundRemove<Ann> for Tree<Ann> {
+    // leaves the slot empty
+    fn remove_child(
+        &mut self,
+        ofs: usize,
+    ) -> Result<OwnedChild<Self, Ann>, CanonError>
+    where
+        Ann: Annotation<u64>,
+    {
+        Ok(match self.leaves.get_mut(ofs) {
+            Some(slot) => {
+                slot.take().map_or(OwnedChild::Empty, OwnedChild::Leaf)
+            }
+            None => {
+                OwnedChild::Node(self.nodes.remove(ofs - self.leaves.len()))
+            }
+        })
+    }
+}
+
+fn leaves<C>(collection: &C) -> Vec<u64>
+where
+    C: for<'a> First<'a, Ann, Leaf = u64>,
+{
+    match collection.first().unwrap() {
+        Some(branch) => branch.into_iter().map(|leaf| *leaf.unwrap()).collect(),
+        None => vec![],
+    }
+}
+
+#[test]
+fn edit_list() {
+    let n: u64 = 16;
+
+    let mut list = LinkedList::<_, Ann>::new();
+
+    for i in (0..n).rev() {
+        list.insert(i)
+    }
+
+    assert_eq!(list.nth_mut(15).unwrap().unwrap().remove().unwrap(), 15);
+    assert_eq!(list.nth_mut(3).unwrap().unwrap().remove().unwrap(), 3);
+    assert_eq!(list.first_mut().unwrap().unwrap().remove().unwrap(), 0);
+    assert_eq!(leaves(&list), [1, 2, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14]);
+    assert_eq!(*list.max_key().unwrap().unwrap(), 14);
+    assert!(list.nth(12).unwrap().is_some());
+    assert!(list.nth(13).unwrap().is_none());
+
+    list.nth_mut(2).unwrap().unwrap().insert_before(3).unwrap();
+    list.first_mut()
+        .unwrap()
+        .unwrap()
+        .insert_before(100)
+        .unwrap();
+    assert_eq!(leaves(&list)[..6], [100, 1, 2, 3, 4, 5]);
+    assert_eq!(*list.max_key().unwrap().unwrap(), 100);
+    assert!(list.nth(14).unwrap().is_some());
+
+    let mut branch = list.first_mut().unwrap().unwrap();
+    assert_eq!(branch.replace_leaf(0), 100);
+    drop(branch);
+    assert_eq!(*list.max_key().unwrap().unwrap(), 14);
+
+    while let Some(branch) = list.first_mut().unwrap() {
+        branch.remove().unwrap();
+    }
+    assert!(list.max_key().unwrap().is_none());
+}
+
+#[test]
+fn edit_tree() {
+    let mut tree = tree::<Ann>(
+        vec![Some(0)],
+        vec![
+            tree(vec![Some(1), Some(2)], vec![]),
+            tree(vec![], vec![tree(vec![Some(3), Some(9)], vec![])]),
+        ],
+    );
+
+    // the removed leaves leave empty slots behind
+    assert_eq!(tree.nth_mut(4).unwrap().unwrap().remove().unwrap(), 9);
+    assert_eq!(tree.nth_mut(1).unwrap().unwrap().remove().unwrap(), 1);
+    assert_eq!(leaves(&tree), [0, 2, 3]);
+    assert_eq!(*tree.max_key().unwrap().unwrap(), 3);
+    assert_eq!(*tree.nth(2).unwrap().unwrap(), 3);
+
+    tree.nth_mut(2).unwrap().unwrap().insert_before(7).unwrap();
+    assert_eq!(leaves(&tree), [0, 2, 7, 3]);
+    assert_eq!(*tree.max_key().unwrap().unwrap(), 7);
+    assert_eq!(*tree.nth(3).unwrap().unwrap(), 3);
+
+    assert_eq!(tree.nth_mut(2).unwrap().unwrap().replace_leaf(1), 7);
+    assert_eq!(*tree.max_key().unwrap().unwrap(), 3);
+}
@@ -0,0 +1,71 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+//
+// Copyright (c) DUSK NETWORK. All rights reserved.
+
+mod linked_list;
+use linked_list::LinkedList;
+
+use canonical_derive::Canon;
+use microkelvin::{GetKey, Keyed, MaxKey, SortedLeaves};
+
+impl<T, A> SortedLeaves for LinkedList<T, A> {}
+
+#[derive(PartialEq, Clone, Canon, Debug)]
+struct Entry {
+    key: u64,
+    value: u64,
+}
+
+impl Keyed<u64> for Entry {
+    fn key(&self) -> &u64 {
+        &self.key
+    }
+}
+
+#[test]
+fn get_by_key() {
+    let n: u64 = 256;
+
+    let mut list = LinkedList::<_, MaxKey<u64>>::new();
+
+    // only even keys, inserted in reverse to keep the list sorted
+    for i in (0..n).rev() {
+        list.insert(Entry {
+            key: i * 2,
+            value: i,
+        });
+    }
+
+    for i in 0..n {
+        assert_eq!(list.get(&(i * 2)).unwrap().unwrap().value, i);
+        assert!(list.get(&(i * 2 + 1)).unwrap().is_none());
+    }
+}
+
+#[test]
+fn get_mut_by_key() {
+    let n: u64 = 256;
+
+    let mut list = LinkedList::<_, MaxKey<u64>>::new();
+
+    for i in (0..n).rev() {
+        list.insert(i);
+    }
+
+    assert!(list.get_mut(&n).unwrap().is_none());
+
+    // bumping the last key keeps the list sorted
+    *list.get_mut(&(n - 1)).unwrap().unwrap() = n;
+
+    assert!(list.get(&(n - 1)).unwrap().is_none());
+    assert_eq!(*list.get(&n).unwrap().unwrap(), n);
+}
+
+#[test]
+fn get_empty() {
+    let list = LinkedList::<u64, MaxKey<u64>>::new();
+
+    assert!(list.get(&0).unwrap().is_none());
+}
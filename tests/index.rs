@@ -0,0 +1,77 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+//
+// Copyright (c) DUSK NETWORK. All rights reserved.
+
+use rand::{prelude::SliceRandom, thread_rng};
+
+mod linked_list;
+use linked_list::LinkedList;
+
+mod tree;
+use tree::tree;
+
+use microkelvin::{Cardinality, GetMaxKey, Last, MaxKey, Nth};
+
+#[test]
+fn index_of_nth() {
+    let n: u64 = 256;
+
+    let mut list = LinkedList::<_, Cardinality>::new();
+
+    for i in 0..n {
+        list.insert(i)
+    }
+
+    for i in 0..n {
+        assert_eq!(list.nth(i).unwrap().unwrap().index(), i);
+        assert_eq!(list.nth_mut(i).unwrap().unwrap().index(), i);
+    }
+
+    assert_eq!(list.last().unwrap().unwrap().index(), n - 1);
+}
+
+#[test]
+fn index_with_siblings() {
+    // leaves and subtrees side by side, with empty slots
+    let tree = tree::<Cardinality>(
+        vec![Some(0), None, Some(1)],
+        vec![
+            tree(vec![Some(2), None, Some(3)], vec![]),
+            tree(vec![None], vec![tree(vec![Some(4), Some(5)], vec![])]),
+            tree(vec![Some(6)], vec![]),
+        ],
+    );
+
+    for i in 0..7 {
+        let branch = tree.nth(i).unwrap().unwrap();
+        assert_eq!(*branch, i);
+        assert_eq!(branch.index(), i);
+    }
+}
+
+#[test]
+fn index_of_max_key() {
+    let n: u64 = 256;
+
+    let mut keys: Vec<u64> = (0..n).collect();
+    keys.shuffle(&mut thread_rng());
+
+    let mut list = LinkedList::<_, (Cardinality, MaxKey<u64>)>::new();
+
+    for key in &keys {
+        list.insert(*key)
+    }
+
+    let max = list.max_key().unwrap().unwrap();
+    assert_eq!(*max, n - 1);
+
+    // elements are prepended, so the last inserted is at index 0
+    let position = keys.iter().position(|key| *key == n - 1).unwrap() as u64;
+    let index = max.index();
+    assert_eq!(index, n - position - 1);
+    drop(max);
+
+    assert_eq!(*list.nth(index).unwrap().unwrap(), n - 1);
+}
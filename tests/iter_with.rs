@@ -0,0 +1,92 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+//
+// Copyright (c) DUSK NETWORK. All rights reserved.
+
+mod linked_list;
+use linked_list::LinkedList;
+
+use microkelvin::{
+    AllLeaves, Cardinality, First, GetMaxKey, IterWith, MaxKey, Nth, WalkerExt,
+};
+
+fn list(n: u64) -> LinkedList<u64, (Cardinality, MaxKey<u64>)> {
+    let mut list = LinkedList::new();
+
+    // inserted in reverse to keep the list in ascending order
+    for i in (0..n).rev() {
+        list.insert(i)
+    }
+
+    list
+}
+
+#[test]
+fn resume_from_branch() {
+    let list = list(32);
+
+    // the leaf pointed at by the branch is yielded first, regardless of the
+    // walker
+    let resumed: Vec<u64> = list
+        .nth(11)
+        .unwrap()
+        .unwrap()
+        .iter_with(AllLeaves.filter_leaves(|l: &u64| l.is_multiple_of(5)))
+        .map(|leaf| *leaf.unwrap())
+        .collect();
+    assert_eq!(resumed, [11, 15, 20, 25, 30]);
+
+    let resumed: Vec<u64> = list
+        .max_key()
+        .unwrap()
+        .unwrap()
+        .iter_with(AllLeaves)
+        .map(|leaf| *leaf.unwrap())
+        .collect();
+    assert_eq!(resumed, [31]);
+}
+
+#[test]
+fn resume_mutable() {
+    let mut list = list(32);
+
+    let iter = list.nth_mut(20).unwrap().unwrap().iter_mut_with(AllLeaves);
+    for leaf in iter.take(3) {
+        *leaf.unwrap() += 100;
+    }
+
+    let leaves: Vec<u64> = list
+        .first()
+        .unwrap()
+        .unwrap()
+        .into_iter()
+        .map(|leaf| *leaf.unwrap())
+        .skip(19)
+        .take(5)
+        .collect();
+    assert_eq!(leaves, [19, 120, 121, 122, 23]);
+}
+
+#[test]
+fn compound_iter_with() {
+    let mut list = list(32);
+
+    let odd: Vec<u64> = list
+        .iter_with(AllLeaves.filter_leaves(|l: &u64| l % 2 == 1).take_leaves(4))
+        .map(|leaf| *leaf.unwrap())
+        .collect();
+    assert_eq!(odd, [1, 3, 5, 7]);
+
+    for leaf in list.iter_mut_with(AllLeaves.skip_leaves(30)) {
+        *leaf.unwrap() = 0;
+    }
+
+    let tail: Vec<u64> = list
+        .iter_with(AllLeaves)
+        .rev()
+        .take(3)
+        .map(|leaf| *leaf.unwrap())
+        .collect();
+    assert_eq!(tail, [0, 0, 29]);
+}
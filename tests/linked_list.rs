@@ -4,23 +4,21 @@
 //
 // Copyright (c) DUSK NETWORK. All rights reserved.
 
-use canonical::Canon;
+use canonical::{Canon, CanonError};
 use canonical_derive::Canon;
 use microkelvin::{
-    Annotated, Annotation, Child, ChildMut, Combine, Compound, First,
-    MutableLeaves,
+    Annotated, Annotation, Child, ChildMut, Combine, Compound, CompoundInsert,
+    CompoundRemove, First, MutableLeaves, OwnedChild,
 };
 
-#[derive(Clone, Canon, Debug)]
+#[derive(Clone, Canon, Debug, Default)]
 pub enum LinkedList<T, A> {
+    #[default]
     Empty,
-    Node { val: T, next: Annotated<Self, A> },
-}
-
-impl<T, A> Default for LinkedList<T, A> {
-    fn default() -> Self {
-        LinkedList::Empty
-    }
+    Node {
+        val: T,
+        next: Annotated<Self, A>,
+    },
 }
 
 impl<T, A> Compound<A> for LinkedList<T, A>
@@ -30,7 +28,7 @@ where
 {
     type Leaf = T;
 
-    fn child(&self, ofs: usize) -> Child<Self, A>
+    fn child(&self, ofs: usize) -> Child<'_, Self, A>
     where
         A: Annotation<Self::Leaf>,
     {
@@ -42,7 +40,7 @@ where
         }
     }
 
-    fn child_mut(&mut self, ofs: usize) -> ChildMut<Self, A>
+    fn child_mut(&mut self, ofs: usize) -> ChildMut<'_, Self, A>
     where
         A: Annotation<Self::Leaf>,
     {
@@ -57,6 +55,55 @@ where
 
 impl<T, A> MutableLeaves for LinkedList<T, A> {}
 
+impl<T, A> CompoundInsert<A> for LinkedList<T, A>
+where
+    T: Canon,
+    A: Canon + Combine<Self, A>,
+{
+    fn insert_leaf(&mut self, ofs: usize, leaf: T) -> Result<(), CanonError>
+    where
+        A: Annotation<T>,
+    {
+        match (&mut *self, ofs) {
+            (_, 0) => self.insert(leaf),
+            (LinkedList::Node { next, .. }, 1) => {
+                next.val_mut()?.insert_leaf(0, leaf)?
+            }
+            _ => panic!("Insertion past the end of the node"),
+        }
+        Ok(())
+    }
+}
+
+impl<T, A> CompoundRemove<A> for LinkedList<T, A>
+where
+    T: Canon,
+    A: Canon + Combine<Self, A>,
+{
+    fn remove_child(
+        &mut self,
+        ofs: usize,
+    ) -> Result<OwnedChild<Self, A>, CanonError>
+    where
+        A: Annotation<T>,
+    {
+        match (&mut *self, ofs) {
+            (LinkedList::Node { next, .. }, 0) => {
+                // the rest of the list takes the place of the node
+                let rest = core::mem::take(&mut *next.val_mut()?);
+                match core::mem::replace(self, rest) {
+                    LinkedList::Node { val, .. } => Ok(OwnedChild::Leaf(val)),
+                    LinkedList::Empty => unreachable!(),
+                }
+            }
+            (LinkedList::Node { next, .. }, 1) => Ok(OwnedChild::Node(
+                core::mem::replace(next, Annotated::new(LinkedList::Empty)),
+            )),
+            _ => Ok(OwnedChild::Empty),
+        }
+    }
+}
+
 impl<T, A> LinkedList<T, A>
 where
     Self: Compound<A>,
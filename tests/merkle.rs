@@ -0,0 +1,126 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+//
+// Copyright (c) DUSK NETWORK. All rights reserved.
+
+mod linked_list;
+use linked_list::LinkedList;
+
+mod tree;
+use tree::tree;
+
+use canonical::{Canon, EncodeToVec, Source};
+use canonical_derive::Canon;
+use microkelvin::{
+    verify_proof, Blake2b, Cardinality, Combine, MerkleProof, MerkleRoot, Nth,
+};
+use microkelvin_derive::Annotation;
+
+#[derive(Default, Clone, Canon, Annotation)]
+struct Anno {
+    card: Cardinality,
+    root: MerkleRoot<Blake2b>,
+}
+
+#[test]
+fn merkle_root_changes() {
+    let mut a = LinkedList::<u64, MerkleRoot>::new();
+    let mut b = LinkedList::<u64, MerkleRoot>::new();
+
+    for i in 0..16 {
+        a.insert(i);
+        b.insert(i);
+    }
+
+    let root_a: MerkleRoot = Combine::combine(&a);
+    let root_b: MerkleRoot = Combine::combine(&b);
+
+    assert_eq!(root_a, root_b);
+
+    b.insert(16);
+
+    let root_b: MerkleRoot = Combine::combine(&b);
+
+    assert!(root_a != root_b);
+}
+
+#[test]
+fn merkle_proofs() {
+    let n: u64 = 128;
+
+    let mut list = LinkedList::<u64, Anno>::new();
+
+    for i in 0..n {
+        list.insert(i);
+    }
+
+    let root: MerkleRoot = Combine::<_, Anno>::combine(&list);
+
+    for i in 0..n {
+        let branch = list.nth(i).unwrap().unwrap();
+        let proof = branch.proof::<Blake2b>();
+
+        assert_eq!(proof.levels.len(), branch.depth());
+        assert!(verify_proof(&root, &*branch, &proof));
+        assert!(!verify_proof(&root, &(*branch + 1), &proof));
+    }
+}
+
+#[test]
+fn merkle_proof_encoding() {
+    let n: u64 = 16;
+
+    let mut list = LinkedList::<u64, Anno>::new();
+
+    for i in 0..n {
+        list.insert(i);
+    }
+
+    let root: MerkleRoot = Combine::<_, Anno>::combine(&list);
+
+    let branch = list.nth(7).unwrap().unwrap();
+    let proof = branch.proof::<Blake2b>();
+
+    let bytes = proof.encode_to_vec();
+    let decoded =
+        MerkleProof::<[u8; 32]>::decode(&mut Source::new(&bytes)).unwrap();
+
+    assert_eq!(proof, decoded);
+    assert!(verify_proof(&root, &*branch, &decoded));
+}
+
+#[test]
+fn merkle_root_in_tuple() {
+    let n: u64 = 16;
+
+    let mut list = LinkedList::<u64, (MerkleRoot, Cardinality)>::new();
+
+    for i in 0..n {
+        list.insert(i);
+    }
+
+    let root: MerkleRoot =
+        Combine::<_, (MerkleRoot, Cardinality)>::combine(&list);
+
+    let branch = list.nth(3).unwrap().unwrap();
+    assert!(verify_proof(&root, &*branch, &branch.proof::<Blake2b>()));
+}
+
+#[test]
+fn empty_slots() {
+    type Ann = (MerkleRoot, Cardinality);
+
+    let tree = tree::<Ann>(vec![None, Some(1)], vec![]);
+    let root: MerkleRoot = Combine::<_, Ann>::combine(&tree);
+
+    let branch = tree.nth(0).unwrap().unwrap();
+    let mut proof = branch.proof::<Blake2b>();
+
+    assert_eq!(proof.levels[0].before, [None]);
+    assert!(verify_proof(&root, &*branch, &proof));
+
+    // an empty slot is not mistaken for a child with the default digest
+    proof.levels[0].before[0] = Some(Default::default());
+    assert!(!verify_proof(&root, &*branch, &proof));
+}
@@ -0,0 +1,79 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+//
+// Copyright (c) DUSK NETWORK. All rights reserved.
+
+use rand::{prelude::SliceRandom, thread_rng};
+
+mod linked_list;
+use linked_list::LinkedList;
+
+use canonical_derive::Canon;
+use microkelvin::{GetMinKey, Keyed, MinKey};
+
+#[derive(PartialEq, Clone, Canon, Debug)]
+struct TestLeaf {
+    key: u64,
+    other: (),
+}
+
+impl Keyed<u64> for TestLeaf {
+    fn key(&self) -> &u64 {
+        &self.key
+    }
+}
+
+#[test]
+fn minimum() {
+    let n: u64 = 1024;
+
+    let mut keys = vec![];
+
+    for i in 0..n {
+        keys.push(i + 1)
+    }
+
+    keys.shuffle(&mut thread_rng());
+
+    let mut list = LinkedList::<_, MinKey<u64>>::new();
+
+    for key in keys {
+        list.insert(TestLeaf { key, other: () });
+    }
+
+    let min = list.min_key().unwrap().unwrap();
+
+    assert_eq!(*min, TestLeaf { key: 1, other: () })
+}
+
+#[test]
+fn minimum_mut() {
+    let n: u64 = 1024;
+
+    let mut keys = vec![];
+
+    for i in 0..n {
+        keys.push(i)
+    }
+
+    keys.shuffle(&mut thread_rng());
+
+    let mut list = LinkedList::<_, MinKey<u64>>::new();
+
+    for key in keys {
+        list.insert(key);
+    }
+
+    // bump the minimum past the maximum, the next smallest should surface
+    *list.min_key_mut().unwrap().unwrap() = n;
+
+    assert_eq!(*list.min_key().unwrap().unwrap(), 1);
+}
+
+#[test]
+fn minimum_empty() {
+    let list = LinkedList::<u64, MinKey<u64>>::new();
+
+    assert!(list.min_key().unwrap().is_none());
+}
@@ -13,7 +13,8 @@ use linked_list::LinkedList;
 
 use canonical_derive::Canon;
 use microkelvin::{
-    Annotation, Cardinality, Combine, Compound, GetMaxKey, Keyed, MaxKey,
+    Annotation, Cardinality, Combine, Compound, First, GetMaxKey, GetMinKey,
+    Keyed, MaxKey, MinKey, Nth,
 };
 
 #[derive(Default, Clone, Canon)]
@@ -102,3 +103,59 @@ fn maximum_multiple() {
         }
     )
 }
+
+#[test]
+fn tuple_annotation() {
+    let n: u64 = 1024;
+
+    let mut keys = vec![];
+
+    for i in 0..n {
+        keys.push(i)
+    }
+
+    keys.shuffle(&mut thread_rng());
+
+    let mut list = LinkedList::<_, (Cardinality, MaxKey<u64>)>::new();
+
+    for key in keys.iter() {
+        list.insert(TestLeaf {
+            key: *key,
+            other: (),
+        });
+    }
+
+    assert_eq!(
+        *list.max_key().unwrap().unwrap(),
+        TestLeaf {
+            key: 1023,
+            other: ()
+        }
+    );
+
+    // the list is in reverse insertion order
+    for i in 0..n {
+        assert_eq!(
+            list.nth(i).unwrap().unwrap().key,
+            keys[(n - i - 1) as usize]
+        );
+    }
+
+    assert_eq!(list.first().unwrap().unwrap().key, keys[(n - 1) as usize]);
+}
+
+#[test]
+fn triple_annotation() {
+    let n: u64 = 1024;
+
+    let mut list =
+        LinkedList::<_, (MaxKey<u64>, Cardinality, MinKey<u64>)>::new();
+
+    for i in 0..n {
+        list.insert(i);
+    }
+
+    assert_eq!(*list.max_key().unwrap().unwrap(), n - 1);
+    assert_eq!(*list.min_key().unwrap().unwrap(), 0);
+    assert_eq!(*list.nth(0).unwrap().unwrap(), n - 1);
+}
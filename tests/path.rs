@@ -0,0 +1,79 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+//
+// Copyright (c) DUSK NETWORK. All rights reserved.
+
+mod linked_list;
+use linked_list::LinkedList;
+
+mod tree;
+use tree::tree;
+
+use canonical::{Canon, EncodeToVec, Source};
+use microkelvin::{Branch, BranchMut, BranchPath, Cardinality, Nth, PathError};
+
+#[test]
+fn replay_paths() {
+    let n: u64 = 64;
+
+    let mut list = LinkedList::<_, Cardinality>::new();
+
+    for i in 0..n {
+        list.insert(i)
+    }
+
+    for i in 0..n {
+        let path = list.nth(i).unwrap().unwrap().to_path();
+
+        let bytes = path.encode_to_vec();
+        let decoded = BranchPath::decode(&mut Source::new(&bytes)).unwrap();
+        assert_eq!(decoded, path);
+
+        let branch = Branch::from_path(&list, &decoded).unwrap().unwrap();
+        assert_eq!(*branch, n - i - 1);
+    }
+
+    let path = list.nth_mut(10).unwrap().unwrap().to_path();
+    *BranchMut::from_path(&mut list, &path).unwrap().unwrap() = 1000;
+    assert_eq!(*list.nth(10).unwrap().unwrap(), 1000);
+}
+
+#[test]
+fn invalid_paths() {
+    // leaves 0 and 1, then an empty slot, then a subtree with the leaf 2
+    let tree = tree::<Cardinality>(
+        vec![Some(0), Some(1), None],
+        vec![tree(vec![Some(2)], vec![])],
+    );
+
+    let path = |offsets: &[usize]| BranchPath::from(offsets);
+
+    assert_eq!(*Branch::from_path(&tree, &path(&[1])).unwrap().unwrap(), 1);
+    assert_eq!(
+        *Branch::from_path(&tree, &path(&[3, 0])).unwrap().unwrap(),
+        2
+    );
+
+    // empty slots, the end of a node and paths ending at a node
+    assert!(Branch::from_path(&tree, &path(&[2])).unwrap().is_none());
+    assert!(Branch::from_path(&tree, &path(&[3, 1])).unwrap().is_none());
+    assert!(Branch::from_path(&tree, &path(&[3])).unwrap().is_none());
+    assert!(Branch::from_path(&tree, &path(&[])).unwrap().is_none());
+
+    // paths continuing past a leaf
+    assert!(matches!(
+        Branch::from_path(&tree, &path(&[0, 0])),
+        Err(PathError::TooLong)
+    ));
+    assert!(matches!(
+        Branch::from_path(&tree, &path(&[3, 0, 0])),
+        Err(PathError::TooLong)
+    ));
+
+    // continuing past an empty slot or the end of a node is not an error
+    assert!(Branch::from_path(&tree, &path(&[2, 0])).unwrap().is_none());
+    assert!(Branch::from_path(&tree, &path(&[3, 1, 0]))
+        .unwrap()
+        .is_none());
+}
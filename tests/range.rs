@@ -0,0 +1,121 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+//
+// Copyright (c) DUSK NETWORK. All rights reserved.
+
+use core::ops::{Bound, RangeBounds};
+
+mod linked_list;
+use linked_list::LinkedList;
+
+use microkelvin::{GetRange, MaxKey, SortedLeaves};
+
+impl<T, A> SortedLeaves for LinkedList<T, A> {}
+
+fn sorted_list(n: u64) -> LinkedList<u64, MaxKey<u64>> {
+    let mut list = LinkedList::new();
+
+    // only even keys, inserted in reverse to keep the list sorted
+    for i in (0..n).rev() {
+        list.insert(i * 2);
+    }
+
+    list
+}
+
+fn keys<R>(list: &LinkedList<u64, MaxKey<u64>>, range: R) -> Vec<u64>
+where
+    R: RangeBounds<u64>,
+{
+    list.range(range)
+        .unwrap()
+        .map(|leaf| *leaf.unwrap())
+        .collect()
+}
+
+#[test]
+fn range_bounds() {
+    let list = sorted_list(64);
+
+    assert_eq!(
+        keys(&list, (Bound::Included(10), Bound::Excluded(16))),
+        [10, 12, 14]
+    );
+    assert_eq!(
+        keys(&list, (Bound::Excluded(10), Bound::Included(16))),
+        [12, 14, 16]
+    );
+    assert_eq!(
+        keys(&list, (Bound::Included(11), Bound::Excluded(13))),
+        [12]
+    );
+    assert_eq!(keys(&list, (Bound::Unbounded, Bound::Excluded(4))), [0, 2]);
+    assert_eq!(
+        keys(&list, (Bound::Included(122), Bound::Unbounded)),
+        [122, 124, 126]
+    );
+    assert_eq!(
+        keys(&list, (Bound::<u64>::Unbounded, Bound::Unbounded)).len(),
+        64
+    );
+}
+
+#[test]
+fn range_syntax() {
+    let list = sorted_list(64);
+
+    assert_eq!(keys(&list, 3..9), [4, 6, 8]);
+    assert_eq!(keys(&list, ..=2), [0, 2]);
+    assert_eq!(keys(&list, 124..), [124, 126]);
+}
+
+#[test]
+fn range_empty() {
+    let list = sorted_list(64);
+
+    assert_eq!(list.range(13..14).unwrap().count(), 0);
+    assert_eq!(list.range(200..).unwrap().count(), 0);
+    assert_eq!(list.range(10..10).unwrap().count(), 0);
+
+    let empty = LinkedList::<u64, MaxKey<u64>>::new();
+    assert_eq!(empty.range(..).unwrap().count(), 0);
+}
+
+#[test]
+fn range_mut() {
+    let mut list = sorted_list(64);
+
+    // decrementing the keys preserves the order, since they are spaced by two
+    for leaf in list.range_mut(20..40).unwrap() {
+        *leaf.unwrap() -= 1;
+    }
+
+    assert_eq!(
+        keys(&list, 16..42),
+        [16, 18, 19, 21, 23, 25, 27, 29, 31, 33, 35, 37, 40]
+    );
+}
+
+#[test]
+fn range_rev() {
+    let mut list = sorted_list(64);
+
+    let rev: Vec<u64> = list
+        .range(3..=10)
+        .unwrap()
+        .rev()
+        .map(|leaf| *leaf.unwrap())
+        .collect();
+    assert_eq!(rev, [10, 8, 6, 4]);
+
+    let mut iter = list.range(100..).unwrap();
+    assert_eq!(*iter.next_back().unwrap().unwrap(), 126);
+    assert_eq!(*iter.next().unwrap().unwrap(), 100);
+    drop(iter);
+
+    for leaf in list.range_mut(..6).unwrap().rev() {
+        *leaf.unwrap() += 1;
+    }
+    assert_eq!(keys(&list, ..8), [1, 3, 5, 6]);
+}
@@ -0,0 +1,194 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+//
+// Copyright (c) DUSK NETWORK. All rights reserved.
+
+mod linked_list;
+use linked_list::LinkedList;
+
+mod tree;
+use tree::{tree, Tree};
+
+use microkelvin::{Cardinality, First, Last, Nth};
+
+// The leaves 0 to 4, with empty slots and empty nodes on both ends
+fn uneven_tree() -> Tree<Cardinality> {
+    tree(
+        vec![Some(0), None, Some(1)],
+        vec![
+            tree(vec![Some(2), Some(3)], vec![]),
+            tree(vec![], vec![tree(vec![Some(4)], vec![])]),
+            tree(vec![None], vec![]),
+        ],
+    )
+}
+
+fn list(n: u64) -> LinkedList<u64, Cardinality> {
+    let mut list = LinkedList::new();
+
+    for i in 0..n {
+        list.insert(i)
+    }
+
+    list
+}
+
+// Alternates between the front and the back of the iterator
+fn zigzag<'a, I>(mut iter: I) -> Vec<u64>
+where
+    I: DoubleEndedIterator<Item = &'a u64>,
+{
+    let mut res = vec![];
+    let mut back = false;
+    while let Some(leaf) = if back { iter.next_back() } else { iter.next() } {
+        res.push(*leaf);
+        back = !back;
+    }
+    res
+}
+
+#[test]
+fn last() {
+    let n: u64 = 64;
+
+    let mut list = list(n);
+
+    assert_eq!(*list.last().unwrap().unwrap(), 0);
+
+    *list.last_mut().unwrap().unwrap() = 1000;
+    assert_eq!(*list.nth(n - 1).unwrap().unwrap(), 1000);
+
+    let empty = LinkedList::<u64, Cardinality>::new();
+    assert!(empty.last().unwrap().is_none());
+
+    let tree = uneven_tree();
+    assert_eq!(*tree.last().unwrap().unwrap(), 4);
+}
+
+#[test]
+fn nth_back() {
+    let n: u64 = 64;
+
+    let mut list = list(n);
+
+    for i in 0..n {
+        assert_eq!(*list.nth_back(i).unwrap().unwrap(), i)
+    }
+    assert!(list.nth_back(n).unwrap().is_none());
+
+    *list.nth_back_mut(3).unwrap().unwrap() = 1000;
+    assert_eq!(*list.nth(n - 4).unwrap().unwrap(), 1000);
+
+    let tree = uneven_tree();
+    for i in 0..5 {
+        assert_eq!(*tree.nth_back(i).unwrap().unwrap(), 4 - i)
+    }
+    assert!(tree.nth_back(5).unwrap().is_none());
+}
+
+#[test]
+fn iterate_rev() {
+    let n: u64 = 64;
+
+    let list = list(n);
+
+    let all: Vec<u64> = list
+        .first()
+        .unwrap()
+        .unwrap()
+        .into_iter()
+        .rev()
+        .map(|leaf| *leaf.unwrap())
+        .collect();
+    assert_eq!(all, (0..n).collect::<Vec<_>>());
+
+    let tree = uneven_tree();
+    let all: Vec<u64> = tree
+        .first()
+        .unwrap()
+        .unwrap()
+        .into_iter()
+        .rev()
+        .map(|leaf| *leaf.unwrap())
+        .collect();
+    assert_eq!(all, [4, 3, 2, 1, 0]);
+}
+
+#[test]
+fn iterate_rev_from_branch() {
+    let n: u64 = 64;
+
+    let list = list(n);
+
+    let from_branch: Vec<u64> = list
+        .nth(10)
+        .unwrap()
+        .unwrap()
+        .into_iter_rev()
+        .map(|leaf| *leaf.unwrap())
+        .collect();
+    assert_eq!(from_branch, (n - 11..n).collect::<Vec<_>>());
+
+    let tree = uneven_tree();
+    let from_branch: Vec<u64> = tree
+        .nth(3)
+        .unwrap()
+        .unwrap()
+        .into_iter_rev()
+        .map(|leaf| *leaf.unwrap())
+        .collect();
+    assert_eq!(from_branch, [3, 2, 1, 0]);
+}
+
+#[test]
+fn iterate_both_ends() {
+    let n: u64 = 9;
+
+    let list = list(n);
+
+    let iter = list.first().unwrap().unwrap().into_iter();
+    assert_eq!(
+        zigzag(iter.map(|leaf| leaf.unwrap())),
+        [8, 0, 7, 1, 6, 2, 5, 3, 4]
+    );
+
+    // starting in the middle, the ends meet without yielding twice
+    let iter = list.nth(4).unwrap().unwrap().into_iter();
+    assert_eq!(zigzag(iter.map(|leaf| leaf.unwrap())), [4, 0, 3, 1, 2]);
+
+    let tree = uneven_tree();
+    let mut iter = tree.first().unwrap().unwrap().into_iter();
+    assert_eq!(*iter.next_back().unwrap().unwrap(), 4);
+    assert_eq!(*iter.next().unwrap().unwrap(), 0);
+    assert_eq!(*iter.next_back().unwrap().unwrap(), 3);
+    assert_eq!(*iter.next().unwrap().unwrap(), 1);
+    assert_eq!(*iter.next().unwrap().unwrap(), 2);
+    assert!(iter.next_back().is_none());
+    assert!(iter.next().is_none());
+}
+
+#[test]
+fn iterate_rev_mutable() {
+    let n: u64 = 32;
+
+    let mut list = list(n);
+
+    let leaves = list.first_mut().unwrap().unwrap().into_iter().rev();
+    for (value, leaf) in (0..).zip(leaves) {
+        *leaf.unwrap() = value;
+    }
+
+    for i in 0..n {
+        assert_eq!(*list.nth_back(i).unwrap().unwrap(), i)
+    }
+
+    for leaf in list.nth_mut(4).unwrap().unwrap().into_iter_rev() {
+        *leaf.unwrap() += 100;
+    }
+
+    for i in 0..n {
+        let expected = if i < 5 { n - i - 1 + 100 } else { n - i - 1 };
+        assert_eq!(*list.nth(i).unwrap().unwrap(), expected)
+    }
+}
@@ -0,0 +1,235 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+//
+// Copyright (c) DUSK NETWORK. All rights reserved.
+
+use core::ops::Bound;
+
+use microkelvin::{
+    verify_annotations, Branch, ByteLen, ByteOffset, Cardinality, Child,
+    Compound, First, Last, Nth, NthByte, Rope,
+};
+use rand::{thread_rng, Rng};
+
+fn random_bytes(n: usize) -> Vec<u8> {
+    let mut rng = thread_rng();
+    (0..n).map(|_| rng.gen()).collect()
+}
+
+fn bytes(rope: &Rope<ByteLen>) -> Vec<u8> {
+    let mut bytes = vec![];
+    if let Some(mut reader) = rope.reader(0).unwrap() {
+        reader.read_to_end(&mut bytes).unwrap();
+    }
+    bytes
+}
+
+// Checks that all nodes other than the root are at least half full
+fn assert_filled(node: &Rope<ByteLen>, root: bool) {
+    let mut len = 0;
+    while let Child::Node(child) = node.child(len) {
+        assert_filled(&child.val().unwrap(), false);
+        len += 1;
+    }
+    while let Child::Leaf(_) = node.child(len) {
+        len += 1;
+    }
+    assert!(root || len >= 4, "Node with {} children", len);
+}
+
+// Checks that all chunks are at the same depth, and nodes are filled
+fn assert_balanced(rope: &Rope<ByteLen>) {
+    if rope.is_empty() {
+        return;
+    }
+    assert_filled(rope, true);
+
+    let depth = rope.first().unwrap().unwrap().depth();
+    assert_eq!(rope.last().unwrap().unwrap().depth(), depth);
+
+    let step = rope.len() / 50 + 1;
+    for ofs in (0..rope.len()).step_by(step as usize) {
+        assert_eq!(rope.nth_byte(ofs).unwrap().unwrap().depth(), depth);
+    }
+}
+
+#[test]
+fn from_bytes() {
+    for n in [0, 1, 255, 256, 257, 10_000, 100_000] {
+        let data = random_bytes(n);
+        let rope = Rope::<ByteLen>::from_bytes(&data).unwrap();
+
+        assert_eq!(rope.len(), n as u64);
+        assert_eq!(bytes(&rope), data);
+        assert_balanced(&rope);
+    }
+}
+
+#[test]
+fn nth_byte() {
+    let data = random_bytes(10_000);
+    let rope = Rope::<ByteLen>::from_bytes(&data).unwrap();
+
+    for ofs in (0..data.len()).step_by(97) {
+        let mut walker = ByteOffset::new(ofs as u64);
+        let branch = Branch::walk(&rope, &mut walker).unwrap().unwrap();
+        assert_eq!(branch[walker.remaining() as usize], data[ofs]);
+        drop(branch);
+
+        let chunk = rope.nth_byte(ofs as u64).unwrap().unwrap();
+        assert!(chunk.contains(&data[ofs]));
+    }
+
+    assert!(rope.nth_byte(data.len() as u64).unwrap().is_none());
+}
+
+#[test]
+fn reader() {
+    let data = random_bytes(5000);
+    let rope = Rope::<ByteLen>::from_bytes(&data).unwrap();
+
+    let mut reader = rope.reader(1234).unwrap().unwrap();
+    let mut read = vec![];
+    let mut buf = [0u8; 100];
+    loop {
+        let n = reader.read(&mut buf).unwrap();
+        if n == 0 {
+            break;
+        }
+        read.extend_from_slice(&buf[..n]);
+    }
+    assert_eq!(read, &data[1234..]);
+
+    assert!(rope.reader(5000).unwrap().is_none());
+}
+
+#[test]
+fn append() {
+    let mut rng = thread_rng();
+    let mut rope = Rope::<ByteLen>::new();
+    let mut model = vec![];
+
+    for _ in 0..100 {
+        let data = random_bytes(rng.gen_range(0..3000));
+        let other = Rope::from_bytes(&data).unwrap();
+
+        // append both larger and smaller ropes on either side
+        if rng.gen() {
+            rope.append(other).unwrap();
+            model.extend_from_slice(&data);
+        } else {
+            let mut other = other;
+            other.append(rope).unwrap();
+            rope = other;
+            model.splice(0..0, data);
+        }
+
+        assert_eq!(rope.len(), model.len() as u64);
+        assert_balanced(&rope);
+    }
+
+    assert_eq!(bytes(&rope), model);
+    assert!(verify_annotations(&rope).unwrap().is_empty());
+}
+
+#[test]
+fn split_off() {
+    let mut rng = thread_rng();
+    let data = random_bytes(50_000);
+    let rope = Rope::<ByteLen>::from_bytes(&data).unwrap();
+
+    for _ in 0..50 {
+        let at = rng.gen_range(0..=data.len());
+
+        let mut left = rope.clone();
+        let right = left.split_off(at as u64).unwrap();
+
+        assert_eq!(bytes(&left), &data[..at]);
+        assert_eq!(bytes(&right), &data[at..]);
+        assert_balanced(&left);
+        assert_balanced(&right);
+        assert!(verify_annotations(&left).unwrap().is_empty());
+        assert!(verify_annotations(&right).unwrap().is_empty());
+
+        // splitting leaves the original untouched
+        assert_eq!(rope.len(), data.len() as u64);
+    }
+    assert_eq!(bytes(&rope), data);
+}
+
+#[test]
+fn split_and_rejoin() {
+    let data = random_bytes(20_000);
+    let mut rope = Rope::<ByteLen>::from_bytes(&data).unwrap();
+
+    let mut parts = vec![];
+    for at in [15_000, 9_999, 4_321, 100, 1] {
+        parts.push(rope.split_off(at).unwrap());
+    }
+    for part in parts.into_iter().rev() {
+        rope.append(part).unwrap();
+    }
+
+    assert_eq!(bytes(&rope), data);
+    assert_balanced(&rope);
+}
+
+#[test]
+fn slice() {
+    let mut rng = thread_rng();
+    let data = random_bytes(30_000);
+    let rope = Rope::<ByteLen>::from_bytes(&data).unwrap();
+
+    for _ in 0..50 {
+        let a = rng.gen_range(0..=data.len());
+        let b = rng.gen_range(a..=data.len());
+
+        let slice = rope.slice(a as u64..b as u64).unwrap().unwrap();
+        assert_eq!(bytes(&slice), &data[a..b]);
+        assert_balanced(&slice);
+    }
+
+    assert_eq!(bytes(&rope.slice(..).unwrap().unwrap()), data);
+    assert_eq!(bytes(&rope.slice(10..=19).unwrap().unwrap()), &data[10..20]);
+}
+
+#[test]
+fn slice_out_of_bounds() {
+    let rope = Rope::<ByteLen>::from_bytes(&random_bytes(100)).unwrap();
+
+    assert!(rope.slice(..=u64::MAX).unwrap().is_none());
+    assert!(rope.slice(50..101).unwrap().is_none());
+    assert!(rope
+        .slice((Bound::Excluded(100), Bound::Unbounded))
+        .unwrap()
+        .is_none());
+    assert!(rope.slice(100..).unwrap().unwrap().is_empty());
+}
+
+#[test]
+fn repeated_slices() {
+    let data = random_bytes(100_000);
+    let mut rope = Rope::<ByteLen>::from_bytes(&data).unwrap();
+    let mut model = &data[..];
+
+    // trimming a few bytes off both ends over and over keeps nodes filled
+    while model.len() > 1000 {
+        let len = rope.len();
+        rope = rope.slice(7..len - 300).unwrap().unwrap();
+        model = &model[7..model.len() - 300];
+        assert_balanced(&rope);
+    }
+    assert_eq!(bytes(&rope), model);
+}
+
+#[test]
+fn with_cardinality() {
+    let data = random_bytes(1000);
+    let rope = Rope::<(Cardinality, ByteLen)>::from_bytes(&data).unwrap();
+
+    // the chunks are counted as leaves
+    let last = rope.nth(3).unwrap().unwrap();
+    assert_eq!(&last[..], &data[768..]);
+    assert!(rope.nth(4).unwrap().is_none());
+}
@@ -0,0 +1,109 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+//
+// Copyright (c) DUSK NETWORK. All rights reserved.
+
+use rand::{prelude::SliceRandom, thread_rng};
+
+mod linked_list;
+use linked_list::LinkedList;
+
+use canonical_derive::Canon;
+use microkelvin::{Keyed, MaxKey, Search};
+
+#[derive(Clone, Canon, Debug, PartialEq)]
+struct Note {
+    height: u64,
+    value: u64,
+}
+
+impl Keyed<u64> for Note {
+    fn key(&self) -> &u64 {
+        &self.height
+    }
+}
+
+fn above(max: &MaxKey<u64>, height: u64) -> bool {
+    match max {
+        MaxKey::Maximum(max) => *max > height,
+        MaxKey::NegativeInfinity => false,
+    }
+}
+
+#[test]
+fn search_above_height() {
+    let n: u64 = 256;
+
+    let mut heights: Vec<u64> = (0..n).collect();
+    heights.shuffle(&mut thread_rng());
+
+    let mut list = LinkedList::<_, MaxKey<u64>>::new();
+
+    for height in &heights {
+        list.insert(Note {
+            height: *height,
+            value: height * 10,
+        })
+    }
+
+    let found: Vec<u64> = list
+        .search(|max| above(max, 200), |note| note.height > 200)
+        .map(|note| note.unwrap().height)
+        .collect();
+
+    // the list yields the notes in reverse insertion order
+    let expected: Vec<u64> =
+        heights.iter().rev().copied().filter(|h| *h > 200).collect();
+
+    assert_eq!(found, expected);
+}
+
+#[test]
+fn search_prunes_subtrees() {
+    let n: u64 = 256;
+
+    let mut list = LinkedList::<_, MaxKey<u64>>::new();
+
+    // the highest notes come first
+    for height in 0..n {
+        list.insert(Note { height, value: 0 })
+    }
+
+    let mut descended = 0;
+    let found = list
+        .search(
+            |max| {
+                descended += 1;
+                above(max, n - 4)
+            },
+            |note| note.height > n - 4,
+        )
+        .count();
+
+    assert_eq!(found, 3);
+    // the rest of the list is never loaded
+    assert!(descended <= 4);
+}
+
+#[test]
+fn search_mut() {
+    let n: u64 = 64;
+
+    let mut list = LinkedList::<_, MaxKey<u64>>::new();
+
+    for height in 0..n {
+        list.insert(Note { height, value: 0 })
+    }
+
+    for note in list.search_mut(|max| above(max, 60), |note| note.height > 60) {
+        note.unwrap().value = 1;
+    }
+
+    let values: Vec<u64> = list
+        .search(|_| true, |note| note.value == 1)
+        .map(|note| note.unwrap().height)
+        .collect();
+
+    assert_eq!(values, [63, 62, 61]);
+}
@@ -0,0 +1,92 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+//
+// Copyright (c) DUSK NETWORK. All rights reserved.
+
+mod linked_list;
+use linked_list::LinkedList;
+
+use canonical_derive::Canon;
+use microkelvin::{Annotated, Sum, Weighted, WeightedNth};
+
+#[derive(PartialEq, Clone, Canon, Debug)]
+struct Stake {
+    id: u64,
+    amount: u64,
+}
+
+impl Weighted<u64> for Stake {
+    fn weight(&self) -> &u64 {
+        &self.amount
+    }
+}
+
+#[test]
+fn total_sum() {
+    let n: u64 = 1024;
+
+    let mut list = LinkedList::<_, Sum<u64>>::new();
+
+    for i in 0..n {
+        list.insert(i)
+    }
+
+    let annotated = Annotated::<_, Sum<u64>>::new(list);
+
+    assert_eq!(*annotated.annotation().value(), n * (n - 1) / 2);
+}
+
+#[test]
+fn weighted_nth() {
+    let n: u64 = 64;
+
+    let mut list = LinkedList::<_, Sum<u64>>::new();
+
+    // inserted in reverse, so the list holds stakes with id 0..n in order,
+    // each with an amount of `id + 1`
+    for id in (0..n).rev() {
+        list.insert(Stake { id, amount: id + 1 })
+    }
+
+    let mut cumulative = 0;
+
+    for id in 0..n {
+        for w in cumulative..cumulative + id + 1 {
+            assert_eq!(list.weighted_nth(w).unwrap().unwrap().id, id);
+        }
+        cumulative += id + 1;
+    }
+
+    assert!(list.weighted_nth(cumulative).unwrap().is_none());
+}
+
+#[test]
+fn weighted_nth_skips_zero_weights() {
+    let mut list = LinkedList::<_, Sum<u64>>::new();
+
+    list.insert(Stake { id: 2, amount: 3 });
+    list.insert(Stake { id: 1, amount: 0 });
+    list.insert(Stake { id: 0, amount: 0 });
+
+    assert_eq!(list.weighted_nth(0).unwrap().unwrap().id, 2);
+}
+
+#[test]
+fn weighted_nth_mut() {
+    let n: u64 = 16;
+
+    let mut list = LinkedList::<_, Sum<u64>>::new();
+
+    for _ in 0..n {
+        list.insert(1)
+    }
+
+    for i in 0..n {
+        *list.weighted_nth_mut(i * 2).unwrap().unwrap() += 1;
+    }
+
+    let annotated = Annotated::<_, Sum<u64>>::new(list);
+
+    assert_eq!(*annotated.annotation().value(), n * 2);
+}
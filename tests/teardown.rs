@@ -0,0 +1,161 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+//
+// Copyright (c) DUSK NETWORK. All rights reserved.
+
+use canonical::{Canon, EncodeToVec, Source};
+use canonical_derive::Canon;
+use microkelvin::{
+    teardown, Annotated, Annotation, Cardinality, Child, ChildMut, Combine,
+    Compound, MutableLeaves, Nth,
+};
+
+// A linked list tearing itself down when dropped
+#[derive(Clone, Canon, Debug, Default)]
+enum Stack<T, A>
+where
+    T: Canon,
+    A: Canon + Annotation<T>,
+{
+    #[default]
+    Empty,
+    Node {
+        val: T,
+        next: Annotated<Self, A>,
+    },
+}
+
+impl<T, A> Drop for Stack<T, A>
+where
+    T: Canon,
+    A: Canon + Annotation<T>,
+{
+    fn drop(&mut self) {
+        teardown(self)
+    }
+}
+
+impl<T, A> Compound<A> for Stack<T, A>
+where
+    T: Canon,
+    A: Canon + Annotation<T>,
+{
+    type Leaf = T;
+
+    fn child(&self, ofs: usize) -> Child<'_, Self, A>
+    where
+        A: Annotation<Self::Leaf>,
+    {
+        match (self, ofs) {
+            (Stack::Node { val, .. }, 0) => Child::Leaf(val),
+            (Stack::Node { next, .. }, 1) => Child::Node(next),
+            _ => Child::EndOfNode,
+        }
+    }
+
+    fn child_mut(&mut self, ofs: usize) -> ChildMut<'_, Self, A>
+    where
+        A: Annotation<Self::Leaf>,
+    {
+        match (self, ofs) {
+            (Stack::Node { val, .. }, 0) => ChildMut::Leaf(val),
+            (Stack::Node { next, .. }, 1) => ChildMut::Node(next),
+            _ => ChildMut::EndOfNode,
+        }
+    }
+}
+
+impl<T, A> MutableLeaves for Stack<T, A>
+where
+    T: Canon,
+    A: Canon + Annotation<T>,
+{
+}
+
+impl<T, A> Stack<T, A>
+where
+    T: Canon,
+    A: Canon + Annotation<T> + Combine<Self, A>,
+{
+    fn new() -> Self {
+        Default::default()
+    }
+
+    fn push(&mut self, t: T) {
+        let rest = core::mem::take(self);
+        *self = Stack::Node {
+            val: t,
+            next: Annotated::new(rest),
+        };
+    }
+}
+
+#[test]
+fn drop_long_list() {
+    let n: u64 = 1_000_000;
+
+    let mut list = Stack::<_, ()>::new();
+
+    for i in 0..n {
+        list.push(i)
+    }
+
+    drop(list);
+}
+
+#[test]
+fn drop_shared_list() {
+    let n: u64 = 1000;
+
+    let mut list = Stack::<_, Cardinality>::new();
+
+    for i in 0..n {
+        list.push(i)
+    }
+
+    // tearing down a list leaves its clones intact
+    let clone = list.clone();
+    drop(list);
+
+    for i in 0..n {
+        assert_eq!(*clone.nth(i).unwrap().unwrap(), n - i - 1)
+    }
+
+    // and the other way around
+    let mut list = clone.clone();
+    drop(clone);
+
+    *list.nth_mut(500).unwrap().unwrap() = 0;
+    let clone = list.clone();
+    drop(list);
+
+    assert_eq!(*clone.nth(500).unwrap().unwrap(), 0);
+    assert_eq!(*clone.nth(999).unwrap().unwrap(), 0);
+    assert!(clone.nth(1000).unwrap().is_none());
+}
+
+#[test]
+fn drop_loaded_list() {
+    let n: u64 = 100_000;
+
+    let mut list = Stack::<_, Cardinality>::new();
+
+    for i in 0..n {
+        list.push(i);
+        // encoding recurses down to the nodes encoded before, keep it short
+        if i % 10 == 0 {
+            list.encode_to_vec();
+        }
+    }
+
+    let bytes = list.encode_to_vec();
+    drop(list);
+
+    // nodes loaded from the store are torn down as well
+    let list =
+        Stack::<u64, Cardinality>::decode(&mut Source::new(&bytes)).unwrap();
+    assert_eq!(*list.nth(n - 1).unwrap().unwrap(), 0);
+
+    drop(list);
+}
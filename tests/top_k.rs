@@ -0,0 +1,74 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+//
+// Copyright (c) DUSK NETWORK. All rights reserved.
+
+use rand::{prelude::SliceRandom, thread_rng};
+
+mod linked_list;
+use linked_list::LinkedList;
+
+mod tree;
+use tree::balanced;
+
+use microkelvin::{BottomK, MaxKey, MinKey, TopK};
+
+type Anno = (MaxKey<u64>, MinKey<u64>);
+
+fn shuffled(n: u64) -> Vec<u64> {
+    let mut keys: Vec<u64> = (0..n).collect();
+    keys.shuffle(&mut thread_rng());
+    keys
+}
+
+#[test]
+fn top_and_bottom_list() {
+    let n: u64 = 256;
+
+    let mut list = LinkedList::<_, Anno>::new();
+
+    for key in shuffled(n) {
+        list.insert(key)
+    }
+
+    let top: Vec<u64> = list.top_k(5).unwrap().into_iter().copied().collect();
+    assert_eq!(top, [255, 254, 253, 252, 251]);
+
+    let bottom: Vec<u64> =
+        list.bottom_k(3).unwrap().into_iter().copied().collect();
+    assert_eq!(bottom, [0, 1, 2]);
+
+    assert_eq!(list.top_k(0).unwrap().len(), 0);
+    assert_eq!(list.top_k(1000).unwrap().len(), n as usize);
+}
+
+#[test]
+fn top_and_bottom_tree() {
+    let n: u64 = 1000;
+
+    let tree = balanced::<Anno>(&shuffled(n));
+
+    let top: Vec<u64> = tree.top_k(10).unwrap().into_iter().copied().collect();
+    assert_eq!(top, (990..n).rev().collect::<Vec<_>>());
+
+    let bottom: Vec<u64> =
+        tree.bottom_k(10).unwrap().into_iter().copied().collect();
+    assert_eq!(bottom, (0..10).collect::<Vec<_>>());
+
+    let all: Vec<u64> = tree
+        .bottom_k(n as usize)
+        .unwrap()
+        .into_iter()
+        .copied()
+        .collect();
+    assert_eq!(all, (0..n).collect::<Vec<_>>());
+}
+
+#[test]
+fn empty() {
+    let list = LinkedList::<u64, Anno>::new();
+
+    assert!(list.top_k(3).unwrap().is_empty());
+    assert!(list.bottom_k(3).unwrap().is_empty());
+}
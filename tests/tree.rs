@@ -0,0 +1,67 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+//
+// Copyright (c) DUSK NETWORK. All rights reserved.
+
+use canonical::Canon;
+use canonical_derive::Canon;
+use microkelvin::{
+    Annotated, Annotation, Cardinality, Combine, MutableLeaves, Nth,
+};
+use microkelvin_derive::Compound;
+
+// A tree with its leaves, some of them empty, in front of its child nodes
+#[derive(Clone, Canon, Debug, Default, Compound)]
+pub struct Tree<A> {
+    #[leaf]
+    pub leaves: Vec<Option<u64>>,
+    #[node]
+    pub nodes: Vec<Annotated<Self, A>>,
+}
+
+impl<A> MutableLeaves for Tree<A> {}
+
+pub fn tree<A>(leaves: Vec<Option<u64>>, nodes: Vec<Tree<A>>) -> Tree<A>
+where
+    A: Canon + Annotation<u64> + Combine<Tree<A>, A>,
+{
+    Tree {
+        leaves,
+        nodes: nodes.into_iter().map(Annotated::new).collect(),
+    }
+}
+
+// A tree with four children per node and the leaves at the bottom
+pub fn balanced<A>(leaves: &[u64]) -> Tree<A>
+where
+    A: Canon + Annotation<u64> + Combine<Tree<A>, A>,
+{
+    if leaves.len() <= 4 {
+        tree(leaves.iter().copied().map(Some).collect(), vec![])
+    } else {
+        let chunk = leaves.len().div_ceil(4);
+        tree(vec![], leaves.chunks(chunk).map(balanced).collect())
+    }
+}
+
+#[test]
+fn leaves_in_order() {
+    let uneven = tree::<Cardinality>(
+        vec![Some(0), None],
+        vec![
+            tree(vec![Some(1)], vec![]),
+            tree(vec![], vec![tree(vec![None, Some(2)], vec![])]),
+        ],
+    );
+    for i in 0..3 {
+        assert_eq!(*uneven.nth(i).unwrap().unwrap(), i);
+    }
+    assert!(uneven.nth(3).unwrap().is_none());
+
+    let leaves: Vec<u64> = (0..100).collect();
+    let balanced = balanced::<Cardinality>(&leaves);
+    for i in 0..100 {
+        assert_eq!(*balanced.nth(i).unwrap().unwrap(), i);
+    }
+}
@@ -0,0 +1,187 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+//
+// Copyright (c) DUSK NETWORK. All rights reserved.
+
+use microkelvin::{
+    verify_annotations, verify_proof, Blake2b, Branch, Combine, KeyPrefix,
+    MerkleRoot, Trie,
+};
+use rand::{prelude::SliceRandom, thread_rng, Rng};
+
+type Key = [u8; 32];
+
+fn keys(n: usize) -> Vec<Key> {
+    let mut rng = thread_rng();
+    (0..n).map(|_| rng.gen()).collect()
+}
+
+fn trie(keys: &[Key]) -> Trie<Key, u64, MerkleRoot> {
+    let mut trie = Trie::new();
+    for (i, key) in keys.iter().enumerate() {
+        assert_eq!(trie.insert(*key, i as u64).unwrap(), None);
+    }
+    trie
+}
+
+fn nibbles(key: &[u8]) -> impl Iterator<Item = usize> + '_ {
+    key.iter()
+        .flat_map(|byte| vec![(byte >> 4) as usize, (byte & 0xf) as usize])
+}
+
+#[test]
+fn insert_get() {
+    let keys = keys(1000);
+    let mut trie = trie(&keys);
+
+    for (i, key) in keys.iter().enumerate() {
+        assert_eq!(*trie.get(key).unwrap().unwrap(), i as u64);
+    }
+
+    let absent = [0xff; 32];
+    assert!(trie.get(&absent).unwrap().is_none());
+
+    assert_eq!(trie.insert(keys[3], 42).unwrap(), Some(3));
+    assert_eq!(*trie.get(&keys[3]).unwrap().unwrap(), 42);
+}
+
+#[test]
+fn get_mut() {
+    let keys = keys(200);
+    let mut trie = trie(&keys);
+
+    for key in &keys {
+        *trie.get_mut(key).unwrap().unwrap() += 1000;
+    }
+    assert!(trie.get_mut(&[0; 32]).unwrap().is_none());
+
+    for (i, key) in keys.iter().enumerate() {
+        assert_eq!(*trie.get(key).unwrap().unwrap(), i as u64 + 1000);
+    }
+    assert!(verify_annotations(&trie).unwrap().is_empty());
+}
+
+#[test]
+fn path_follows_nibbles() {
+    let keys = keys(500);
+    let trie = trie(&keys);
+
+    for key in &keys {
+        let branch = Branch::walk(&trie, KeyPrefix::new(key)).unwrap().unwrap();
+        let path = branch.to_path();
+
+        assert!(path
+            .offsets()
+            .eq(nibbles(key).take(path.len()).map(|n| n as u64)));
+    }
+}
+
+#[test]
+fn remove() {
+    let mut keys = keys(1000);
+    let mut trie = trie(&keys);
+
+    keys.shuffle(&mut thread_rng());
+    for (i, key) in keys.iter().enumerate() {
+        assert!(trie.remove(key).unwrap().is_some());
+        assert_eq!(trie.remove(key).unwrap(), None);
+
+        if i % 100 == 0 {
+            for key in &keys[i + 1..] {
+                assert!(trie.get(key).unwrap().is_some());
+            }
+        }
+    }
+
+    assert!(trie.is_empty());
+}
+
+#[test]
+fn keys_differing_in_last_nibble() {
+    let mut trie = Trie::<[u8; 2], u64, MerkleRoot>::new();
+
+    let keys = [[0x12, 0x34], [0x12, 0x35], [0x12, 0x30], [0x02, 0x34]];
+    for (i, key) in keys.iter().enumerate() {
+        assert_eq!(trie.insert(*key, i as u64).unwrap(), None);
+    }
+
+    for (i, key) in keys.iter().enumerate() {
+        assert_eq!(*trie.get(key).unwrap().unwrap(), i as u64);
+    }
+    assert!(trie.get(&[0x12, 0x36]).unwrap().is_none());
+
+    for (i, key) in keys.iter().enumerate() {
+        assert_eq!(trie.remove(key).unwrap(), Some(i as u64));
+    }
+    assert!(trie.is_empty());
+}
+
+#[test]
+fn shape_independent_of_history() {
+    let keys = keys(300);
+    let extra = self::keys(300);
+
+    let mut shuffled = keys.clone();
+    shuffled.shuffle(&mut thread_rng());
+
+    let mut a = Trie::<Key, u64, MerkleRoot>::new();
+    let mut b = Trie::<Key, u64, MerkleRoot>::new();
+
+    for key in &keys {
+        a.insert(*key, 0).unwrap();
+    }
+    for (key, extra) in shuffled.iter().zip(&extra) {
+        b.insert(*extra, 1).unwrap();
+        b.insert(*key, 0).unwrap();
+    }
+    for extra in &extra {
+        b.remove(extra).unwrap();
+    }
+
+    let root_a: MerkleRoot = Combine::combine(&a);
+    let root_b: MerkleRoot = Combine::combine(&b);
+    assert_eq!(root_a, root_b);
+}
+
+#[test]
+fn prefix() {
+    let mut keys = keys(2000);
+    let trie = trie(&keys);
+    keys.sort();
+
+    for prefix in [&[][..], &[0xab][..], &[0x12, 0x34][..], &keys[7][..]] {
+        let found: Vec<Key> = trie
+            .prefix(prefix)
+            .map(|entry| *entry.unwrap().key())
+            .collect();
+        let expected: Vec<Key> = keys
+            .iter()
+            .filter(|key| key.starts_with(prefix))
+            .cloned()
+            .collect();
+        assert_eq!(found, expected);
+
+        let rev: Vec<Key> = trie
+            .prefix(prefix)
+            .rev()
+            .map(|entry| *entry.unwrap().key())
+            .collect();
+        assert!(rev.iter().eq(expected.iter().rev()));
+    }
+}
+
+#[test]
+fn proofs() {
+    let keys = keys(200);
+    let trie = trie(&keys);
+
+    let root: MerkleRoot = Combine::combine(&trie);
+
+    for key in &keys {
+        let branch = Branch::walk(&trie, KeyPrefix::new(key)).unwrap().unwrap();
+        let proof = branch.proof::<Blake2b>();
+
+        assert!(verify_proof(&root, &*branch, &proof));
+    }
+}
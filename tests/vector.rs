@@ -0,0 +1,152 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+//
+// Copyright (c) DUSK NETWORK. All rights reserved.
+
+use microkelvin::{
+    verify_annotations, Cardinality, First, Last, MaxKey, Nth, Vector,
+};
+
+fn vector(n: u64) -> Vector<u64, Cardinality> {
+    let mut vector = Vector::new();
+
+    for i in 0..n {
+        vector.push(i).unwrap();
+    }
+
+    vector
+}
+
+#[test]
+fn push_nth() {
+    let n: u64 = 1000;
+
+    let vector = vector(n);
+    assert_eq!(vector.len(), n);
+
+    for i in 0..n {
+        assert_eq!(*vector.nth(i).unwrap().unwrap(), i);
+    }
+    assert!(vector.nth(n).unwrap().is_none());
+
+    let all: Vec<u64> = vector
+        .first()
+        .unwrap()
+        .unwrap()
+        .into_iter()
+        .map(|leaf| *leaf.unwrap())
+        .collect();
+    assert_eq!(all, (0..n).collect::<Vec<_>>());
+
+    let rev: Vec<u64> = vector
+        .last()
+        .unwrap()
+        .unwrap()
+        .into_iter_rev()
+        .map(|leaf| *leaf.unwrap())
+        .collect();
+    assert_eq!(rev, (0..n).rev().collect::<Vec<_>>());
+}
+
+#[test]
+fn balanced() {
+    for n in 1..300 {
+        let vector = vector(n);
+
+        // every leaf is at the same depth, the smallest one holding n
+        let mut depth = 1;
+        while 4u64.pow(depth as u32) < n {
+            depth += 1;
+        }
+
+        for i in 0..n {
+            assert_eq!(vector.nth(i).unwrap().unwrap().depth(), depth);
+        }
+    }
+}
+
+#[test]
+fn pop() {
+    let n: u64 = 300;
+
+    let mut vector = vector(n);
+
+    for i in (0..n).rev() {
+        assert_eq!(vector.pop().unwrap(), Some(i));
+        assert_eq!(vector.len(), i);
+
+        if i > 0 {
+            assert_eq!(*vector.last().unwrap().unwrap(), i - 1);
+            let depth = vector.first().unwrap().unwrap().depth();
+            assert_eq!(vector.last().unwrap().unwrap().depth(), depth);
+        }
+    }
+
+    assert!(vector.is_empty());
+    assert_eq!(vector.pop().unwrap(), None);
+
+    // the vector is usable after being emptied
+    vector.push(7).unwrap();
+    assert_eq!(*vector.nth(0).unwrap().unwrap(), 7);
+}
+
+#[test]
+fn push_pop_mixed() {
+    let mut vector = Vector::<u64, Cardinality>::new();
+    let mut model = vec![];
+
+    for i in 0..2000u64 {
+        if i % 3 == 2 {
+            assert_eq!(vector.pop().unwrap(), model.pop());
+        } else {
+            vector.push(i).unwrap();
+            model.push(i);
+        }
+    }
+
+    assert_eq!(vector.len(), model.len() as u64);
+    for (i, value) in model.iter().enumerate() {
+        assert_eq!(*vector.nth(i as u64).unwrap().unwrap(), *value);
+    }
+}
+
+#[test]
+fn persistent() {
+    let n: u64 = 100;
+
+    let mut vector = vector(n);
+    let snapshot = vector.clone();
+
+    for i in 0..n {
+        *vector.nth_mut(i).unwrap().unwrap() += 1000;
+    }
+    vector.push(0).unwrap();
+    vector.pop().unwrap();
+    vector.pop().unwrap();
+
+    assert_eq!(snapshot.len(), n);
+    for i in 0..n {
+        assert_eq!(*snapshot.nth(i).unwrap().unwrap(), i);
+    }
+
+    assert_eq!(vector.len(), n - 1);
+    for i in 0..n - 1 {
+        assert_eq!(*vector.nth(i).unwrap().unwrap(), i + 1000);
+    }
+}
+
+#[test]
+fn annotations() {
+    let mut vector = Vector::<u64, (Cardinality, MaxKey<u64>)>::new();
+
+    for i in 0..500 {
+        vector.push(i * 7 % 500).unwrap();
+    }
+    for _ in 0..100 {
+        vector.pop().unwrap();
+    }
+
+    assert_eq!(vector.len(), 400);
+    assert!(verify_annotations(&vector).unwrap().is_empty());
+}
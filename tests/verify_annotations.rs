@@ -0,0 +1,92 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+//
+// Copyright (c) DUSK NETWORK. All rights reserved.
+
+mod linked_list;
+use linked_list::LinkedList;
+
+mod tree;
+use tree::Tree;
+
+use canonical::{Canon, EncodeToVec, Source};
+use microkelvin::{
+    verify_annotations, Annotated, BranchPath, Cardinality, MaxKey, Nth,
+};
+
+type Ann = (Cardinality, MaxKey<u64>);
+
+fn tree(
+    leaves: Vec<Option<u64>>,
+    nodes: Vec<Annotated<Tree<Ann>, Ann>>,
+) -> Tree<Ann> {
+    Tree { leaves, nodes }
+}
+
+fn leaves(leaves: &[u64]) -> Annotated<Tree<Ann>, Ann> {
+    Annotated::new(tree(leaves.iter().copied().map(Some).collect(), vec![]))
+}
+
+// An annotated node holding the leaves of `node`, but the annotation of
+// `other`, as decoded from a corrupted encoding
+fn stale(
+    node: Annotated<Tree<Ann>, Ann>,
+    other: Annotated<Tree<Ann>, Ann>,
+) -> Annotated<Tree<Ann>, Ann> {
+    let mut bytes = node.encode_to_vec();
+    bytes.truncate(bytes.len() - node.annotation().encoded_len());
+    bytes.extend(other.annotation().encode_to_vec());
+
+    Annotated::decode(&mut Source::new(&bytes)).unwrap()
+}
+
+#[test]
+fn consistent() {
+    let n: u64 = 1000;
+
+    let mut list = LinkedList::<_, Ann>::new();
+
+    for i in 0..n {
+        list.insert(i)
+    }
+
+    assert!(verify_annotations(&list).unwrap().is_empty());
+
+    let tree = tree(
+        vec![Some(0)],
+        vec![
+            leaves(&[1, 2]),
+            Annotated::new(tree(vec![], vec![leaves(&[3])])),
+        ],
+    );
+    assert!(verify_annotations(&tree).unwrap().is_empty());
+}
+
+#[test]
+fn stale_annotations() {
+    let tree = tree(
+        vec![Some(0)],
+        vec![
+            stale(leaves(&[1, 2]), leaves(&[1])),
+            Annotated::new(tree(
+                vec![],
+                vec![leaves(&[3]), stale(leaves(&[4]), leaves(&[100]))],
+            )),
+        ],
+    );
+
+    // the stale cardinality throws off the indices
+    assert_eq!(*tree.nth(2).unwrap().unwrap(), 3);
+
+    let mismatches = verify_annotations(&tree).unwrap();
+    assert_eq!(mismatches.len(), 2);
+
+    assert_eq!(mismatches[0].path, BranchPath::from(&[1][..]));
+    assert_eq!(mismatches[0].cached.0, leaves(&[1]).annotation().0);
+    assert_eq!(mismatches[0].combined.0, leaves(&[1, 2]).annotation().0);
+
+    assert_eq!(mismatches[1].path, BranchPath::from(&[2, 1][..]));
+    assert_eq!(mismatches[1].cached, *leaves(&[100]).annotation());
+    assert_eq!(mismatches[1].combined, *leaves(&[4]).annotation());
+}
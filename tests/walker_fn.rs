@@ -0,0 +1,121 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+//
+// Copyright (c) DUSK NETWORK. All rights reserved.
+
+mod linked_list;
+use linked_list::LinkedList;
+
+use microkelvin::{
+    walker_fn, Branch, BranchMut, Cardinality, IterChild, MaxKey, Step, Walk,
+};
+
+#[test]
+fn closure_nth() {
+    let n: u64 = 64;
+
+    let mut list = LinkedList::<_, Cardinality>::new();
+
+    for i in 0..n {
+        list.insert(i)
+    }
+
+    for i in 0..n {
+        let mut remainder = i;
+
+        let branch = Branch::<_, Cardinality>::walk(
+            &list,
+            walker_fn(|walk: Walk<_, Cardinality>| {
+                for (ofs, child) in walk.children() {
+                    let card = u64::from(&*child.annotation());
+                    if card <= remainder {
+                        remainder -= card;
+                    } else {
+                        match child {
+                            IterChild::Leaf(_) => return Step::Found(ofs),
+                            IterChild::Node(_) => return Step::Into(ofs),
+                        }
+                    }
+                }
+                Step::Abort
+            }),
+        )
+        .unwrap()
+        .unwrap();
+
+        assert_eq!(*branch, n - i - 1);
+    }
+}
+
+#[test]
+fn closure_search() {
+    let n: u64 = 64;
+
+    let mut list = LinkedList::<_, MaxKey<u64>>::new();
+
+    for i in 0..n {
+        list.insert(i)
+    }
+
+    // the first leaf divisible by 7, skipping subtrees of smaller leaves
+    let mut visited = 0;
+    let mut branch = BranchMut::<_, MaxKey<u64>>::walk(
+        &mut list,
+        walker_fn(|walk: Walk<_, MaxKey<u64>>| {
+            visited += 1;
+            for (ofs, child) in walk.children() {
+                match child {
+                    IterChild::Leaf(l) if l % 7 == 0 => {
+                        return Step::Found(ofs)
+                    }
+                    IterChild::Leaf(_) => (),
+                    IterChild::Node(n) => {
+                        if let MaxKey::Maximum(_) = n.annotation() {
+                            return Step::Into(ofs);
+                        }
+                    }
+                }
+            }
+            Step::Advance
+        }),
+    )
+    .unwrap()
+    .unwrap();
+
+    assert_eq!(*branch, 63);
+    *branch = 1000;
+    drop(branch);
+
+    assert_eq!(visited, 1);
+
+    let none =
+        Branch::<_, MaxKey<u64>>::walk(&list, walker_fn(|_| Step::Abort))
+            .unwrap();
+    assert!(none.is_none());
+}
+
+#[test]
+fn closure_walk_back() {
+    let n: u64 = 16;
+
+    let mut list = LinkedList::<_, Cardinality>::new();
+
+    for i in 0..n {
+        list.insert(i)
+    }
+
+    // the children are presented last to first
+    let branch = Branch::<_, Cardinality>::walk_back(
+        &list,
+        walker_fn(|walk: Walk<_, Cardinality>| match walk.children().next() {
+            Some((ofs, IterChild::Leaf(_))) => Step::Found(ofs),
+            Some((ofs, IterChild::Node(_))) => Step::Into(ofs),
+            None => Step::Advance,
+        }),
+    )
+    .unwrap()
+    .unwrap();
+
+    assert_eq!(*branch, 0);
+}